@@ -0,0 +1,79 @@
+// Timing of the cryptanalysis routines on cipher texts of a few lengths.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use vigenere_cipher::analysis::{
+    break_repeating_xor, break_vigenere, friedman, kasiski, rank_periods, NgramModel, ENGLISH_IC,
+};
+use vigenere_cipher::{Alphabet, ByteCipher, ByteOp, NonAlpha, VigenereCipher};
+
+const CORPUS: &str = "It is a truth universally acknowledged, that a single man in possession \
+    of a good fortune, must be in want of a wife. However little known the feelings or views \
+    of such a man may be on his first entering a neighbourhood, this truth is so well fixed in \
+    the minds of the surrounding families, that he is considered the rightful property of some \
+    one or other of their daughters. ";
+
+const QUADGRAMS: &str = include_str!("../src/analysis/english_quadgrams.txt");
+
+const SIZES: [usize; 3] = [1 << 9, 1 << 11, 1 << 13];
+
+fn cipher_text(size: usize) -> String {
+    let plain_text = CORPUS.chars().cycle().take(size).collect::<String>();
+    VigenereCipher::new("AUSTEN")
+        .with_non_alpha(NonAlpha::Preserve)
+        .encrypt(&plain_text)
+}
+
+fn analysis_key_length(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analysis_key_length");
+    let latin = Alphabet::latin();
+
+    for size in SIZES {
+        let text = cipher_text(size);
+        group.bench_with_input(BenchmarkId::new("kasiski", size), &text, |b, text| {
+            b.iter(|| kasiski(black_box(text), &latin, 3, 20))
+        });
+        group.bench_with_input(BenchmarkId::new("rank_periods", size), &text, |b, text| {
+            b.iter(|| rank_periods(black_box(text), &latin, 20))
+        });
+        group.bench_with_input(BenchmarkId::new("friedman", size), &text, |b, text| {
+            b.iter(|| friedman(black_box(text), &latin, ENGLISH_IC))
+        });
+    }
+
+    group.finish();
+}
+
+fn solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    group.sample_size(20);
+
+    for size in SIZES {
+        let text = cipher_text(size);
+        group.bench_with_input(
+            BenchmarkId::new("break_vigenere", size),
+            &text,
+            |b, text| b.iter(|| break_vigenere(black_box(text), 3)),
+        );
+
+        let plain_text = CORPUS.bytes().cycle().take(size).collect::<Vec<u8>>();
+        let data = ByteCipher::new(b"Austen")
+            .with_op(ByteOp::Xor)
+            .encrypt(&plain_text);
+        group.bench_with_input(
+            BenchmarkId::new("break_repeating_xor", size),
+            &data,
+            |b, data| b.iter(|| break_repeating_xor(black_box(data), 3)),
+        );
+    }
+
+    // The built-in models are only built once, so time reading the table.
+    group.bench_function("english_quadgrams", |b| {
+        b.iter(|| NgramModel::from_counts(black_box(QUADGRAMS.as_bytes()), Alphabet::latin()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, analysis_key_length, solve);
+criterion_main!(benches);
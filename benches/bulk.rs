@@ -0,0 +1,39 @@
+// Compares the vectorized fast path for A-Z text with the per-char loop every
+// other alphabet goes through, on the same upper case data.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use vigenere_cipher::{Alphabet, VigenereCipher};
+
+fn bulk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk");
+    let fast = VigenereCipher::new("LEMON");
+    // The same 26 letters, but starting at B they aren't a run of consecutive
+    // characters, so the fast path doesn't apply.
+    let scalar = VigenereCipher::new_in("LEMON", Alphabet::new("BCDEFGHIJKLMNOPQRSTUVWXYZA"));
+
+    for size in [1 << 10, 1 << 16, 1 << 20] {
+        let text = "ATTACKATDAWN"
+            .chars()
+            .cycle()
+            .take(size)
+            .collect::<String>();
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("fast", size), &text, |b, text| {
+            b.iter(|| fast.encrypt(black_box(text)))
+        });
+        group.bench_with_input(BenchmarkId::new("fast_in_place", size), &text, |b, text| {
+            let mut buf = text.clone().into_bytes();
+            b.iter(|| fast.encrypt_in_place(black_box(&mut buf)))
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", size), &text, |b, text| {
+            b.iter(|| scalar.encrypt(black_box(text)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bulk);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+// Throughput of encryption and decryption across input sizes and key
+// lengths, for each of the paths a text can take through the cipher.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use vigenere_cipher::{Alphabet, AutokeyCipher, NonAlpha, VigenereCipher};
+
+const PROSE: &str = "It was the best of times, it was the worst of times, it was the age of \
+    wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of \
+    incredulity, it was the season of Light, it was the season of Darkness. ";
+
+const GREEK: &str = "ΕΝ ΑΡΧΗ ΗΝ Ο ΛΟΓΟΣ ΚΑΙ Ο ΛΟΓΟΣ ΗΝ ΠΡΟΣ ΤΟΝ ΘΕΟΝ ";
+
+const SIZES: [usize; 3] = [1 << 8, 1 << 12, 1 << 16];
+
+// `text` repeated to `size` characters.
+fn repeat(text: &str, size: usize) -> String {
+    text.chars().cycle().take(size).collect()
+}
+
+fn key_of_len(len: usize) -> String {
+    "CRYPTOGRAPHY".chars().cycle().take(len).collect()
+}
+
+fn encrypt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt");
+    let letters = PROSE
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>();
+
+    for size in SIZES {
+        let upper = repeat(&letters, size);
+        let prose = repeat(PROSE, size);
+        group.throughput(Throughput::Bytes(size as u64));
+
+        let cipher = VigenereCipher::new("LEMON");
+        group.bench_with_input(BenchmarkId::new("letters", size), &upper, |b, text| {
+            b.iter(|| cipher.encrypt(black_box(text)))
+        });
+
+        let cipher = VigenereCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+        group.bench_with_input(BenchmarkId::new("prose", size), &prose, |b, text| {
+            b.iter(|| cipher.encrypt(black_box(text)))
+        });
+
+        let cipher = AutokeyCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+        group.bench_with_input(BenchmarkId::new("autokey", size), &prose, |b, text| {
+            b.iter(|| cipher.encrypt(black_box(text)))
+        });
+    }
+
+    group.finish();
+}
+
+fn decrypt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decrypt");
+
+    for size in SIZES {
+        let cipher = VigenereCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+        let prose = cipher.encrypt(&repeat(PROSE, size));
+        group.throughput(Throughput::Bytes(prose.len() as u64));
+        group.bench_with_input(BenchmarkId::new("prose", size), &prose, |b, text| {
+            b.iter(|| cipher.decrypt(black_box(text)))
+        });
+
+        // Not ASCII, so every letter goes through the character loop and
+        // reverse_rotate_index().
+        let cipher =
+            VigenereCipher::new_in("ΚΛΕΙΔΙ", Alphabet::greek()).with_non_alpha(NonAlpha::Preserve);
+        let greek = cipher.encrypt(&repeat(GREEK, size));
+        group.throughput(Throughput::Bytes(greek.len() as u64));
+        group.bench_with_input(BenchmarkId::new("greek", size), &greek, |b, text| {
+            b.iter(|| cipher.decrypt(black_box(text)))
+        });
+    }
+
+    group.finish();
+}
+
+fn cipher_key_length(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cipher_key_length");
+    let prose = repeat(PROSE, 1 << 12);
+    group.throughput(Throughput::Bytes(prose.len() as u64));
+
+    for len in [1, 5, 16, 64, 256] {
+        let cipher = VigenereCipher::new(&key_of_len(len)).with_non_alpha(NonAlpha::Preserve);
+        group.bench_with_input(BenchmarkId::new("encrypt", len), &prose, |b, text| {
+            b.iter(|| cipher.encrypt(black_box(text)))
+        });
+        group.bench_with_input(BenchmarkId::new("decrypt", len), &prose, |b, text| {
+            b.iter(|| cipher.decrypt(black_box(text)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, encrypt, decrypt, cipher_key_length);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+// Counts the letter bigrams, trigrams and quadgrams of English text, for the
+// tables the built-in `NgramModel::english_*` models are read from.
+//
+//     cargo run --release --example english_ngrams -- OUT_DIR TEXT...
+//
+// scripts/english-ngrams.sh fetches the texts the bundled tables were
+// counted from and runs this on them.  Each table is written to OUT_DIR as
+// `english_<name>.txt`, one `NGRAM count` per line, commonest first.  The
+// Project Gutenberg header and license around each book are skipped, and
+// n-grams don't span two files.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+
+use vigenere_cipher::Alphabet;
+
+// The tables written, with the fewest times an n-gram has to occur to be
+// listed.  The rarest quadgrams are left out to keep the table small; the
+// model gives them a floor probability instead.
+const TABLES: [(&str, usize, u64); 3] =
+    [("bigrams", 2, 1), ("trigrams", 3, 1), ("quadgrams", 4, 3)];
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if args.len() < 2 {
+        eprintln!("usage: english_ngrams OUT_DIR TEXT...");
+        process::exit(2);
+    }
+
+    if let Err(e) = run(Path::new(&args[0]), &args[1..]) {
+        eprintln!("english_ngrams: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(out_dir: &Path, paths: &[String]) -> io::Result<()> {
+    let alphabet = Alphabet::latin();
+    let mut texts = Vec::new();
+    for path in paths {
+        let text = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+        let letters = letters(&book_text(&text), &alphabet);
+        println!("{:>10} letters  {}", letters.len(), path);
+        texts.push(letters);
+    }
+    println!(
+        "{:>10} letters in total",
+        texts.iter().map(Vec::len).sum::<usize>()
+    );
+
+    for (name, n, min_count) in TABLES {
+        let mut counts: HashMap<&[char], u64> = HashMap::new();
+        for letters in &texts {
+            for ngram in letters.windows(n) {
+                *counts.entry(ngram).or_default() += 1;
+            }
+        }
+
+        let mut counts = counts
+            .into_iter()
+            .filter(|&(_, count)| count >= min_count)
+            .collect::<Vec<(&[char], u64)>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let path = out_dir.join(format!("english_{}.txt", name));
+        let mut out = BufWriter::new(fs::File::create(&path)?);
+        for (ngram, count) in counts {
+            writeln!(out, "{} {}", ngram.iter().collect::<String>(), count)?;
+        }
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+// The lines of `text` outside the Project Gutenberg headers and licenses.  A
+// file may hold several books, each with its own header, which ends either
+// with a `*** START OF` line or with the end of the old "small print"
+// license.
+fn book_text(text: &str) -> String {
+    let mut keep = !text.contains("*** START OF") && !text.contains("SMALL PRINT!");
+    let mut book = String::new();
+    for line in text.lines() {
+        let upper = line.trim_start_matches('*').trim().to_uppercase();
+        if upper.starts_with("END OF") && upper.contains("PROJECT GUTENBERG") {
+            keep = false;
+        } else if line.starts_with("*** START OF") || line.contains("*END*THE SMALL PRINT") {
+            keep = true;
+        } else if keep {
+            book.push_str(line);
+            book.push('\n');
+        }
+    }
+    book
+}
+
+// The letters of `text`, uppercased, as `NgramModel::train` reads them.
+fn letters(text: &str, alphabet: &Alphabet) -> Vec<char> {
+    text.chars()
+        .filter_map(|c| {
+            let mut upper = c.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(u), None) => alphabet.index_of(u).map(|i| alphabet.char_at(i)),
+                _ => None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,37 @@
+// ByteCipher round trips, and the repeating-key XOR breaker on arbitrary
+// data, which must never panic.
+//
+// Input: an options byte, then the key and the data separated by the first
+// NUL.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vigenere_cipher::analysis::{break_repeating_xor, rank_xor_key_sizes};
+use vigenere_cipher::{ByteCipher, ByteOp};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&options, rest)) = data.split_first() else {
+        return;
+    };
+    let split = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+    let (key, data) = (&rest[..split], rest.get(split + 1..).unwrap_or_default());
+    let op = if options & 1 == 0 {
+        ByteOp::Add
+    } else {
+        ByteOp::Xor
+    };
+
+    if let Ok(cipher) = ByteCipher::try_new(key) {
+        let cipher = cipher.with_op(op);
+        let cipher_text = cipher.encrypt(data);
+        assert_eq!(data.len(), cipher_text.len());
+        assert_eq!(data, &cipher.decrypt(&cipher_text)[..]);
+    }
+
+    for candidate in break_repeating_xor(data, 3) {
+        assert!(!candidate.key.is_empty());
+        assert_eq!(data.len(), candidate.plain_text.len());
+    }
+    let _ = rank_xor_key_sizes(data, usize::from(options));
+});
@@ -0,0 +1,101 @@
+// The other ciphers, chosen by the options byte: encryption must never
+// panic, and decryption must undo it.
+//
+// Input: an options byte, then the key and the text separated by a NUL.  The
+// Quagmire ciphers take their alphabet keywords from the key, up to a second
+// NUL.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vigenere_cipher::{
+    AutokeyCipher, GraphemeAlphabet, GraphemeCipher, NonAlpha, Normalization, PortaCipher,
+    Quagmire, QuagmireCipher, RunningKeyCipher,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&options, rest)) = data.split_first() else {
+        return;
+    };
+    let rest = String::from_utf8_lossy(rest);
+    let (key, text) = rest.split_once('\0').unwrap_or((&rest, ""));
+    let non_alpha = match options / 8 % 3 {
+        0 => NonAlpha::Reject,
+        1 => NonAlpha::Preserve,
+        _ => NonAlpha::PreserveAndAdvance,
+    };
+
+    match options % 8 {
+        0 => {
+            if let Ok(cipher) = AutokeyCipher::try_new(key) {
+                let cipher = cipher.with_non_alpha(non_alpha);
+                if let Ok(cipher_text) = cipher.try_encrypt(text) {
+                    assert_eq!(text, cipher.decrypt(&cipher_text));
+                }
+            }
+        }
+        1 => {
+            let offset = usize::from(options / 32);
+            if let Ok(cipher) = RunningKeyCipher::try_new(key, offset) {
+                let cipher = cipher.with_non_alpha(non_alpha);
+                if let Ok(cipher_text) = cipher.try_encrypt(text) {
+                    assert_eq!(text, cipher.decrypt(&cipher_text));
+                }
+            }
+        }
+        2..=5 => {
+            let (keywords, key) = key.split_once('\0').unwrap_or(("", key));
+            let (plain_keyword, cipher_keyword) = keywords.split_at(keywords.len() / 2);
+            let variant = match options % 8 {
+                2 => Quagmire::I { keyword: keywords },
+                3 => Quagmire::II { keyword: keywords },
+                4 => Quagmire::III { keyword: keywords },
+                _ => Quagmire::IV {
+                    plain_keyword,
+                    cipher_keyword,
+                },
+            };
+            if let Ok(cipher) = QuagmireCipher::try_new(variant, key) {
+                let indicator = text.chars().next().unwrap_or('A');
+                let cipher = match cipher.try_with_indicator(indicator) {
+                    Ok(cipher) => cipher,
+                    Err(_) => return,
+                };
+                let cipher = cipher.with_non_alpha(non_alpha);
+                if let Ok(cipher_text) = cipher.try_encrypt(text) {
+                    assert_eq!(text, cipher.decrypt(&cipher_text));
+                }
+            }
+        }
+        6 => {
+            if let Ok(cipher) = PortaCipher::try_new(key) {
+                let cipher = cipher.with_non_alpha(non_alpha);
+                if let Ok(cipher_text) = cipher.try_apply(text) {
+                    assert_eq!(text, cipher.apply(&cipher_text));
+                }
+            }
+        }
+        _ => {
+            let normalization = if options & 0x80 == 0 {
+                Normalization::Nfc
+            } else {
+                Normalization::Nfd
+            };
+            let Some((letters, key)) = key.split_once('\0') else {
+                return;
+            };
+            let Ok(alphabet) = GraphemeAlphabet::try_new(letters, normalization) else {
+                return;
+            };
+            if let Ok(cipher) = GraphemeCipher::try_new(key, alphabet) {
+                // Only text already in the alphabet's normalization form
+                // comes back byte for byte.
+                let text = normalization.apply(text);
+                let cipher = cipher.with_non_alpha(NonAlpha::Reject);
+                if let Ok(cipher_text) = cipher.try_encrypt(&text) {
+                    assert_eq!(text, cipher.decrypt(&cipher_text));
+                }
+            }
+        }
+    }
+});
@@ -0,0 +1,39 @@
+// Building alphabets and parsing keys: any input must either be rejected
+// with an error or give a key whose shifts are positions in the alphabet.
+//
+// Input: the alphabet and the key separated by a NUL.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vigenere_cipher::{Alphabet, GraphemeAlphabet, Key, Normalization};
+
+fuzz_target!(|data: &[u8]| {
+    let data = String::from_utf8_lossy(data);
+    let (letters, key) = data.split_once('\0').unwrap_or((&data, ""));
+
+    if let Ok(alphabet) = Alphabet::try_new(letters) {
+        assert_eq!(letters.chars().count(), alphabet.size());
+
+        if let Ok(parsed) = Key::parse(key, &alphabet) {
+            assert_eq!(key, parsed.as_str());
+            assert_eq!(key.chars().count(), parsed.shifts().len());
+            assert!(parsed
+                .shifts()
+                .iter()
+                .all(|&i| (i as usize) < alphabet.size()));
+        }
+
+        if let Ok(keyed) = alphabet.keyed(key) {
+            assert_eq!(alphabet.size(), keyed.size());
+        }
+    }
+
+    if let Ok(parsed) = Key::from_digits(key) {
+        assert!(parsed.shifts().iter().all(|&i| i < 10));
+    }
+
+    for normalization in [Normalization::Nfc, Normalization::Nfd] {
+        let _ = GraphemeAlphabet::try_new(letters, normalization);
+    }
+});
@@ -0,0 +1,19 @@
+// The n-gram count table parser: any input must either be rejected with an
+// error or give a model that scores text as a finite number.
+//
+// Input: the table.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vigenere_cipher::analysis::{LanguageModel, NgramModel};
+use vigenere_cipher::Alphabet;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(model) = NgramModel::from_counts(data, Alphabet::latin()) {
+        assert!(model.n() > 0);
+        assert!(model
+            .score("THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG")
+            .is_finite());
+    }
+});
@@ -0,0 +1,52 @@
+// VigenereReader and VigenereWriter over arbitrary bytes, which may not be
+// UTF-8: they must never panic, and whenever they succeed they must agree
+// with enciphering the whole text at once.
+//
+// Input: an options byte, then the text.  The low bits of the options byte
+// pick the chunk size.
+
+#![no_main]
+
+use std::io::{Read, Write};
+
+use libfuzzer_sys::fuzz_target;
+use vigenere_cipher::{NonAlpha, VigenereCipher, VigenereReader, VigenereWriter};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&options, data)) = data.split_first() else {
+        return;
+    };
+    let chunk_size = usize::from(options % 16) + 1;
+    let non_alpha = match options / 16 % 3 {
+        0 => NonAlpha::Reject,
+        1 => NonAlpha::Preserve,
+        _ => NonAlpha::PreserveAndAdvance,
+    };
+    let cipher = VigenereCipher::new("LEMON").with_non_alpha(non_alpha);
+    let whole = std::str::from_utf8(data)
+        .ok()
+        .and_then(|text| cipher.try_encrypt(text).ok());
+
+    let mut writer = VigenereWriter::encrypting(cipher.clone(), Vec::new());
+    let written = data
+        .chunks(chunk_size)
+        .try_for_each(|chunk| writer.write_all(chunk))
+        .and_then(|()| writer.finish());
+    if let Ok(out) = written {
+        assert_eq!(whole.as_deref().map(str::as_bytes), Some(&out[..]));
+    }
+
+    let mut reader = VigenereReader::encrypting(cipher, data);
+    let mut out = Vec::new();
+    let mut buf = vec![0; chunk_size];
+    let read = loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break Ok(()),
+            Ok(n) => out.extend_from_slice(&buf[..n]),
+            Err(e) => break Err(e),
+        }
+    };
+    if read.is_ok() {
+        assert_eq!(whole.as_deref().map(str::as_bytes), Some(&out[..]));
+    }
+});
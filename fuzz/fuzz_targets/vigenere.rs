@@ -0,0 +1,54 @@
+// VigenereCipher over every preset alphabet, mode and tableau: encryption
+// must never panic, must keep the length of the text, and decryption must
+// undo it.  The in-place byte path must agree with the string one.
+//
+// Input: an options byte, then the key and the text separated by a NUL.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vigenere_cipher::{Alphabet, NonAlpha, Tableau, VigenereCipher};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&options, rest)) = data.split_first() else {
+        return;
+    };
+    let rest = String::from_utf8_lossy(rest);
+    let (key, text) = rest.split_once('\0').unwrap_or((&rest, ""));
+
+    let alphabet = match options % 5 {
+        0 => Alphabet::latin(),
+        1 => Alphabet::alphanumeric(),
+        2 => Alphabet::printable_ascii(),
+        3 => Alphabet::cyrillic(),
+        _ => Alphabet::greek(),
+    };
+    let non_alpha = match options / 5 % 3 {
+        0 => NonAlpha::Reject,
+        1 => NonAlpha::Preserve,
+        _ => NonAlpha::PreserveAndAdvance,
+    };
+    let tableau = match options / 15 % 3 {
+        0 => Tableau::Vigenere,
+        1 => Tableau::Beaufort,
+        _ => Tableau::VariantBeaufort,
+    };
+
+    let Ok(cipher) = VigenereCipher::try_new_in(key, alphabet) else {
+        return;
+    };
+    let cipher = cipher.with_non_alpha(non_alpha).with_tableau(tableau);
+
+    let encrypted = cipher.try_encrypt(text);
+    if let Ok(cipher_text) = &encrypted {
+        assert_eq!(text.chars().count(), cipher_text.chars().count());
+
+        assert_eq!(text, cipher.decrypt(cipher_text));
+    }
+
+    if cipher.alphabet().chars().iter().all(char::is_ascii) {
+        let mut bytes = text.as_bytes().to_vec();
+        let in_place = cipher.try_encrypt_in_place(&mut bytes).map(|()| bytes);
+        assert_eq!(encrypted.map(String::into_bytes), in_place);
+    }
+});
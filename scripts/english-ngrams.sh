@@ -0,0 +1,50 @@
+#!/bin/sh
+# Rebuilds src/analysis/english_{bigrams,trigrams,quadgrams}.txt.
+#
+# The tables are counted from public-domain books from Project Gutenberg.
+# The copies used are the ones in the Silesia and Canterbury compression
+# corpora, as packaged in these crates on crates.io, so the exact same files
+# can be fetched and checked against the checksums below:
+#
+#   density-rs 0.16.6  benches/data/dickens.txt  the collected works of
+#                                                Charles Dickens (Silesia)
+#   grep 0.1.0         src/data/sherlock.txt     The Adventures of Sherlock
+#                                                Holmes, Arthur Conan Doyle
+#   snap 0.2.5         data/alice29.txt          Alice's Adventures in
+#                                                Wonderland, Lewis Carroll
+#                      data/asyoulik.txt         As You Like It, William
+#                                                Shakespeare
+#                      data/plrabn12.txt         Paradise Lost, John Milton
+#
+# Run it from the root of the repository.  It needs curl, tar and sha256sum.
+
+set -eu
+
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+# Downloads a crate, checks it and unpacks the given files from it.
+fetch() {
+    name=$1 version=$2 sha256=$3
+    shift 3
+    crate="$work/$name-$version.crate"
+    curl -sSfL -o "$crate" "https://static.crates.io/crates/$name/$name-$version.crate"
+    echo "$sha256  $crate" | sha256sum -c --quiet
+    for file in "$@"; do
+        tar -xzf "$crate" -C "$work" "$name-$version/$file"
+    done
+}
+
+fetch density-rs 0.16.6 61cf8df069b6a076ab2c0103a66054f3cdbaee0294b7870ff1c69a166b8df06c \
+    benches/data/dickens.txt
+fetch grep 0.1.0 c70108c4f04f0927e8fd2dfe2ee2fde572174ac12e6eb08243487c9f6924cccc \
+    src/data/sherlock.txt
+fetch snap 0.2.5 95d697d63d44ad8b78b8d235bf85b34022a78af292c8918527c5f0cffdde7f43 \
+    data/alice29.txt data/asyoulik.txt data/plrabn12.txt
+
+cargo run --release --example english_ngrams -- src/analysis \
+    "$work/density-rs-0.16.6/benches/data/dickens.txt" \
+    "$work/grep-0.1.0/src/data/sherlock.txt" \
+    "$work/snap-0.2.5/data/alice29.txt" \
+    "$work/snap-0.2.5/data/asyoulik.txt" \
+    "$work/snap-0.2.5/data/plrabn12.txt"
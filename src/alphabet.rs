@@ -0,0 +1,307 @@
+//! The [`Alphabet`] a cipher rotates characters within.
+
+use std::collections::HashMap;
+
+use crate::error::CipherError;
+
+/// An ordered set of distinct characters.
+///
+/// A character's position in the alphabet is the numeric value the cipher
+/// rotates, and the number of characters is the modulus it wraps around at.
+/// The default is the uppercase Latin alphabet `A`–`Z`.
+///
+/// ```
+/// use vigenere_cipher::Alphabet;
+///
+/// let alphabet = Alphabet::new("ABC123");
+/// assert_eq!(6, alphabet.size());
+/// assert_eq!(Some(3), alphabet.index_of('1'));
+/// assert_eq!('C', alphabet.char_at(2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+    index: HashMap<char, u32>,
+    // The lowercase form of every letter, or nothing if some letter can't be
+    // lowercased and brought back (see lowercase_forms()).
+    lowercase: HashMap<char, u32>,
+}
+
+impl Alphabet {
+    /// Creates an alphabet from the characters of `chars`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chars` is empty or contains a character more than once.
+    /// Use [`try_new`](Self::try_new) to handle that instead.
+    pub fn new(chars: &str) -> Alphabet {
+        Alphabet::try_new(chars).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates an alphabet from the characters of `chars`, in order, or
+    /// reports why they can't form one.
+    pub fn try_new(chars: &str) -> Result<Alphabet, CipherError> {
+        let mut index = HashMap::new();
+        let mut alphabet_chars = Vec::new();
+
+        for (pos, ch) in chars.chars().enumerate() {
+            if index.insert(ch, pos as u32).is_some() {
+                return Err(CipherError::DuplicateAlphabetChar { pos, ch });
+            }
+            alphabet_chars.push(ch);
+        }
+
+        if alphabet_chars.is_empty() {
+            return Err(CipherError::EmptyAlphabet);
+        }
+
+        let lowercase = lowercase_forms(&alphabet_chars, &index);
+        Ok(Alphabet {
+            chars: alphabet_chars,
+            index,
+            lowercase,
+        })
+    }
+
+    /// The uppercase Latin letters `A`–`Z`.
+    pub fn latin() -> Alphabet {
+        Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+    }
+
+    /// The uppercase Latin letters `A`–`Z` followed by the digits `0`–`9`.
+    pub fn alphanumeric() -> Alphabet {
+        Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")
+    }
+
+    /// Every printable ASCII character, from space through `~`.
+    pub fn printable_ascii() -> Alphabet {
+        Alphabet::new(&(' '..='~').collect::<String>())
+    }
+
+    /// The 33 uppercase letters of the Russian alphabet, `А`–`Я` with `Ё`.
+    pub fn cyrillic() -> Alphabet {
+        Alphabet::new("АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ")
+    }
+
+    /// The 24 uppercase letters of the Greek alphabet, `Α`–`Ω`.
+    pub fn greek() -> Alphabet {
+        Alphabet::new("ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ")
+    }
+
+    /// Builds a keyword-mixed alphabet: the distinct characters of `keyword`
+    /// in the order they first appear, followed by the rest of this alphabet
+    /// in its usual order.
+    ///
+    /// ```
+    /// use vigenere_cipher::Alphabet;
+    ///
+    /// let mixed = Alphabet::latin().keyed("KRYPTOS").unwrap();
+    /// assert_eq!(Alphabet::new("KRYPTOSABCDEFGHIJLMNQUVWXZ"), mixed);
+    /// ```
+    ///
+    /// Every character of `keyword` must be in the alphabet; the first one
+    /// that isn't is reported as [`CipherError::InvalidKeywordChar`].
+    pub fn keyed(&self, keyword: &str) -> Result<Alphabet, CipherError> {
+        let mut mixed = String::with_capacity(self.chars.len());
+        for (pos, ch) in keyword.chars().enumerate() {
+            if !self.contains(ch) {
+                return Err(CipherError::InvalidKeywordChar { pos, ch });
+            }
+            if !mixed.contains(ch) {
+                mixed.push(ch);
+            }
+        }
+
+        for &ch in &self.chars {
+            if !mixed.contains(ch) {
+                mixed.push(ch);
+            }
+        }
+
+        Alphabet::try_new(&mixed)
+    }
+
+    /// Returns the number of characters in the alphabet.
+    pub fn size(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Returns the characters of the alphabet, in order.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Returns whether `c` is in the alphabet.
+    pub fn contains(&self, c: char) -> bool {
+        self.index.contains_key(&c)
+    }
+
+    /// Converts `c` to its position in the alphabet (for `A`–`Z`, A = 0,
+    /// B = 1..), or `None` if it isn't in the alphabet.
+    pub fn index_of(&self, c: char) -> Option<u32> {
+        self.index.get(&c).copied()
+    }
+
+    /// Converts a position in the alphabet back to its character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than [`size`](Self::size).
+    pub fn char_at(&self, i: u32) -> char {
+        self.chars[i as usize]
+    }
+
+    // Like index_of(), but also accepts the lowercase form of a letter in the
+    // alphabet.  The returned flag is set when `c` is a lowercase form, and
+    // char_at_case() turns the enciphered letter back into one.
+    pub(crate) fn fold_index(&self, c: char) -> Option<(u32, bool)> {
+        if let Some(i) = self.index_of(c) {
+            return Some((i, false));
+        }
+        self.lowercase.get(&c).map(|&i| (i, true))
+    }
+
+    // Like index_of(), but also accepts anything whose uppercase form is in
+    // the alphabet, for text that is only read and never given back, such
+    // as a running key's text.
+    pub(crate) fn index_ignoring_case(&self, c: char) -> Option<u32> {
+        self.index_of(c)
+            .or_else(|| self.index_of(single(c.to_uppercase())?))
+    }
+
+    // Converts a position back to its character, lowercased when `lower` is
+    // set.
+    pub(crate) fn char_at_case(&self, i: u32, lower: bool) -> char {
+        let c = self.char_at(i);
+        if lower {
+            single(c.to_lowercase()).unwrap_or(c)
+        } else {
+            c
+        }
+    }
+}
+
+// The character a case mapping gives when it gives exactly one.
+fn single(mut mapped: impl Iterator<Item = char>) -> Option<char> {
+    match (mapped.next(), mapped.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+// Maps the lowercase form of every letter to the letter's position.  A
+// lowercase letter can only be enciphered if it comes back as itself, so
+// every letter needs a lowercase form outside the alphabet that uppercases
+// back to it: Σ has σ, but ς also uppercases to Σ and would come back as σ,
+// and over A-Z with the digits, "th" would come back as "TH" if one of them
+// enciphered to a digit.  If any letter has none, nothing is folded.
+fn lowercase_forms(chars: &[char], index: &HashMap<char, u32>) -> HashMap<char, u32> {
+    chars
+        .iter()
+        .zip(0..)
+        .map(|(&c, i)| {
+            let lower = single(c.to_lowercase())?;
+            let round_trips = lower != c
+                && !index.contains_key(&lower)
+                && single(lower.to_uppercase()) == Some(c);
+            round_trips.then_some((lower, i))
+        })
+        .collect::<Option<HashMap<char, u32>>>()
+        .unwrap_or_default()
+}
+
+// An alphabet of the first `n` CJK ideographs, for testing with alphabets of
+// any size up to 20,992.
+#[cfg(test)]
+pub(crate) fn sized(n: u32) -> Alphabet {
+    Alphabet::new(
+        &(0x4e00..0x4e00 + n)
+            .map(|c| char::from_u32(c).unwrap())
+            .collect::<String>(),
+    )
+}
+
+impl Default for Alphabet {
+    fn default() -> Alphabet {
+        Alphabet::latin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_alpha_pos() {
+        assert_eq!(Some(0), Alphabet::latin().index_of('A'));
+        assert_eq!(None, Alphabet::latin().index_of('a'));
+    }
+
+    #[test]
+    fn test_to_char() {
+        assert_eq!('A', Alphabet::latin().char_at(0));
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(26, Alphabet::latin().size());
+        assert_eq!(36, Alphabet::alphanumeric().size());
+        assert_eq!(95, Alphabet::printable_ascii().size());
+        assert_eq!(33, Alphabet::cyrillic().size());
+        assert_eq!(24, Alphabet::greek().size());
+        assert_eq!(Alphabet::latin(), Alphabet::default());
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        assert_eq!(Err(CipherError::EmptyAlphabet), Alphabet::try_new(""));
+        assert_eq!(
+            Err(CipherError::DuplicateAlphabetChar { pos: 3, ch: 'B' }),
+            Alphabet::try_new("ABCB")
+        );
+    }
+
+    #[test]
+    fn test_fold_index() {
+        let greek = Alphabet::greek();
+        assert_eq!(Some((17, true)), greek.fold_index('σ'));
+        assert_eq!(None, greek.fold_index('ς'));
+        assert_eq!('σ', greek.char_at_case(17, true));
+        assert_eq!(None, Alphabet::latin().fold_index('1'));
+
+        // The digits have no lowercase form to encipher to.
+        assert_eq!(None, Alphabet::alphanumeric().fold_index('t'));
+        // Nor does B, whose lowercase form is a letter of its own.
+        assert_eq!(None, Alphabet::new("ABb").fold_index('a'));
+    }
+
+    #[test]
+    fn test_fold_index_outside_ascii() {
+        // Both uppercase to ASCII letters, but would decrypt as i and s.
+        assert_eq!(None, Alphabet::latin().fold_index('ı'));
+        assert_eq!(None, Alphabet::latin().fold_index('ſ'));
+        assert_eq!(Some((8, true)), Alphabet::latin().fold_index('i'));
+    }
+
+    #[test]
+    fn test_index_ignoring_case() {
+        let alphanumeric = Alphabet::alphanumeric();
+        assert_eq!(Some(19), alphanumeric.index_ignoring_case('t'));
+        assert_eq!(Some(17), Alphabet::greek().index_ignoring_case('ς'));
+        assert_eq!(None, alphanumeric.index_ignoring_case(' '));
+    }
+
+    #[test]
+    fn test_keyed() {
+        let latin = Alphabet::latin();
+        assert_eq!(latin, latin.keyed("").unwrap());
+        assert_eq!(
+            Alphabet::new("HELOABCDFGIJKMNPQRSTUVWXYZ"),
+            latin.keyed("HELLO").unwrap()
+        );
+        assert_eq!(
+            Err(CipherError::InvalidKeywordChar { pos: 1, ch: 'e' }),
+            latin.keyed("Hello")
+        );
+    }
+}
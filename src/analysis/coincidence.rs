@@ -0,0 +1,180 @@
+//! Index of coincidence and the Friedman test.
+//!
+//! The index of coincidence is the chance that two characters picked at
+//! random from a text are the same.  Natural language has a much higher index
+//! than random text, and a Vigenère cipher flattens it out; but every column
+//! of cipher text that was enciphered by the same key character keeps the
+//! index of the plain text.  That makes it possible to recognise the key
+//! length, either by measuring the columns directly or, with the Friedman
+//! test, by how far the index of the whole text has fallen.
+
+use crate::alphabet::Alphabet;
+
+use super::letters;
+
+/// The index of coincidence of English text over `A`–`Z`.
+pub const ENGLISH_IC: f64 = 0.0667;
+
+/// A key length scored by the index of coincidence of its columns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeriodScore {
+    /// The key length the cipher text was split by.
+    pub period: usize,
+    /// The average index of coincidence of the columns.
+    pub mean_ic: f64,
+}
+
+// The index of coincidence of text already converted to alphabet positions.
+pub(crate) fn ic_of(text: &[u32], alphabet_size: usize) -> f64 {
+    let n = text.len();
+    if n < 2 {
+        return 0.0;
+    }
+
+    let mut counts = vec![0usize; alphabet_size];
+    for &i in text {
+        counts[i as usize] += 1;
+    }
+
+    let pairs = counts
+        .iter()
+        .map(|&c| c * c.saturating_sub(1))
+        .sum::<usize>();
+    pairs as f64 / (n * (n - 1)) as f64
+}
+
+// Splits `text` into `period` columns, so that column `j` holds every
+// character enciphered by key character `j`.
+pub(crate) fn columns(text: &[u32], period: usize) -> Vec<Vec<u32>> {
+    let mut columns = vec![Vec::with_capacity(text.len() / period + 1); period];
+    for (pos, &i) in text.iter().enumerate() {
+        columns[pos % period].push(i);
+    }
+    columns
+}
+
+/// Returns the index of coincidence of the characters of `text` that are in
+/// `alphabet`, or 0 if there are fewer than two of them.
+///
+/// ```
+/// use vigenere_cipher::analysis::index_of_coincidence;
+/// use vigenere_cipher::Alphabet;
+///
+/// assert_eq!(1.0, index_of_coincidence("AAAA", &Alphabet::latin()));
+/// assert_eq!(0.0, index_of_coincidence("ABCD", &Alphabet::latin()));
+/// ```
+pub fn index_of_coincidence(text: &str, alphabet: &Alphabet) -> f64 {
+    ic_of(&letters(text, alphabet), alphabet.size())
+}
+
+/// Splits `cipher_text` into `period` columns and returns the index of
+/// coincidence of each.
+///
+/// # Panics
+///
+/// Panics if `period` is 0.
+pub fn column_coincidences(cipher_text: &str, alphabet: &Alphabet, period: usize) -> Vec<f64> {
+    assert!(period > 0, "period must not be 0");
+
+    columns(&letters(cipher_text, alphabet), period)
+        .iter()
+        .map(|column| ic_of(column, alphabet.size()))
+        .collect()
+}
+
+/// Scores every key length from 1 to `max_period` by the average index of
+/// coincidence of its columns, best first.
+///
+/// The right key length brings the average up to that of the plain text
+/// language.  Its multiples do too, so a multiple can outscore it by chance;
+/// with short texts prefer the smallest period near the top.
+pub fn rank_periods(cipher_text: &str, alphabet: &Alphabet, max_period: usize) -> Vec<PeriodScore> {
+    let text = letters(cipher_text, alphabet);
+
+    let mut scores = (1..=max_period)
+        .map(|period| {
+            let columns = columns(&text, period);
+            let total = columns
+                .iter()
+                .map(|column| ic_of(column, alphabet.size()))
+                .sum::<f64>();
+            PeriodScore {
+                period,
+                mean_ic: total / period as f64,
+            }
+        })
+        .collect::<Vec<PeriodScore>>();
+    scores.sort_by(|a, b| b.mean_ic.total_cmp(&a.mean_ic));
+    scores
+}
+
+/// Estimates the key length of `cipher_text` with the Friedman test.
+///
+/// `language_ic` is the index of coincidence of the plain text language,
+/// such as [`ENGLISH_IC`].  The estimate is only a rough guide to the key
+/// length, and is meaningless when the cipher text is as flat as random text
+/// (it is then infinite or negative).
+///
+/// ```
+/// use vigenere_cipher::analysis::{friedman, ENGLISH_IC};
+/// use vigenere_cipher::Alphabet;
+///
+/// // Plain English text looks like it was encrypted with a one letter key.
+/// let plain_text = "ITWASTHEBESTOFTIMESITWASTHEWORSTOFTIMESITWASTHEAGEOFWISDOM";
+/// let estimate = friedman(plain_text, &Alphabet::latin(), ENGLISH_IC);
+/// assert!(0.0 < estimate && estimate < 2.0);
+/// ```
+pub fn friedman(cipher_text: &str, alphabet: &Alphabet, language_ic: f64) -> f64 {
+    let text = letters(cipher_text, alphabet);
+    let n = text.len() as f64;
+    let random_ic = 1.0 / alphabet.size() as f64;
+    let ic = ic_of(&text, alphabet.size());
+
+    (language_ic - random_ic) * n / ((n - 1.0) * ic - n * random_ic + language_ic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SAMPLE_TEXT;
+    use crate::{NonAlpha, VigenereCipher};
+
+    fn encrypt_sample(key: &str) -> String {
+        VigenereCipher::new(key)
+            .with_non_alpha(NonAlpha::Preserve)
+            .encrypt(SAMPLE_TEXT)
+    }
+
+    #[test]
+    fn test_index_of_coincidence() {
+        let latin = Alphabet::latin();
+        assert_eq!(0.0, index_of_coincidence("", &latin));
+        assert_eq!(2.0 / 12.0, index_of_coincidence("a-a-b-c", &latin));
+
+        let plain_ic = index_of_coincidence(SAMPLE_TEXT, &latin);
+        assert!((plain_ic - ENGLISH_IC).abs() < 0.01, "{}", plain_ic);
+
+        let cipher_ic = index_of_coincidence(&encrypt_sample("LEMON"), &latin);
+        assert!(cipher_ic < plain_ic - 0.015, "{}", cipher_ic);
+    }
+
+    #[test]
+    fn test_column_coincidences() {
+        let columns = column_coincidences("ABABAB", &Alphabet::latin(), 2);
+        assert_eq!(vec![1.0, 1.0], columns);
+    }
+
+    #[test]
+    fn test_rank_periods() {
+        let scores = rank_periods(&encrypt_sample("LEMON"), &Alphabet::latin(), 12);
+        assert_eq!(12, scores.len());
+        assert_eq!(0, scores[0].period % 5);
+        assert!(scores[0].mean_ic > 0.055, "{:?}", scores[0]);
+    }
+
+    #[test]
+    fn test_friedman() {
+        let estimate = friedman(&encrypt_sample("LEMON"), &Alphabet::latin(), ENGLISH_IC);
+        assert!((3.0..8.0).contains(&estimate), "{}", estimate);
+    }
+}
@@ -0,0 +1,164 @@
+//! Kasiski examination: estimating the key length from repeated n-grams.
+//!
+//! When the same stretch of plain text happens to line up with the same part
+//! of the key, it encrypts to the same cipher text.  The distance between two
+//! such repetitions is then a multiple of the key length, so lengths that
+//! divide many of the distances are likely candidates.
+
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+
+use super::letters;
+
+/// An n-gram that occurs more than once in the cipher text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repetition {
+    /// The repeated characters.
+    pub ngram: String,
+    /// Where each occurrence starts, counting only characters in the
+    /// alphabet.
+    pub positions: Vec<usize>,
+    /// The distance from each occurrence to the next one.
+    pub distances: Vec<usize>,
+}
+
+/// A key length suggested by the repetitions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyLengthCandidate {
+    /// The suggested key length.
+    pub length: usize,
+    /// How many of the distances `length` is a factor of.
+    pub support: usize,
+}
+
+/// The result of [`kasiski`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kasiski {
+    /// Every repeated n-gram, in order of first occurrence.
+    pub repetitions: Vec<Repetition>,
+    /// Key lengths with any support, best supported first.
+    ///
+    /// Every factor of a distance also divides it, so the factors of the
+    /// real key length score at least as well as the key length itself.
+    /// Ties are ranked longest first, since the longer length accounts for
+    /// the same distances.
+    pub candidates: Vec<KeyLengthCandidate>,
+}
+
+/// Runs a Kasiski examination over `cipher_text`.
+///
+/// Repeated n-grams of `ngram_len` characters are collected, and every key
+/// length from 2 to `max_key_len` is scored by how many of the distances
+/// between consecutive repetitions it divides.
+///
+/// ```
+/// use vigenere_cipher::analysis::kasiski;
+/// use vigenere_cipher::Alphabet;
+///
+/// let result = kasiski("ABCXYZABCQQQABC", &Alphabet::latin(), 3, 10);
+/// assert_eq!(vec![0, 6, 12], result.repetitions[0].positions);
+/// assert_eq!(6, result.candidates[0].length);
+/// ```
+pub fn kasiski(
+    cipher_text: &str,
+    alphabet: &Alphabet,
+    ngram_len: usize,
+    max_key_len: usize,
+) -> Kasiski {
+    let text = letters(cipher_text, alphabet);
+    if ngram_len == 0 || text.len() < ngram_len {
+        return Kasiski {
+            repetitions: Vec::new(),
+            candidates: Vec::new(),
+        };
+    }
+
+    // Positions of every n-gram, remembering the order they first appear
+    // in so the result doesn't depend on hash map iteration order.
+    let mut seen: HashMap<&[u32], Vec<usize>> = HashMap::new();
+    let mut order = Vec::new();
+    for (pos, ngram) in text.windows(ngram_len).enumerate() {
+        let positions = seen.entry(ngram).or_default();
+        if positions.is_empty() {
+            order.push(ngram);
+        }
+        positions.push(pos);
+    }
+
+    let repetitions = order
+        .into_iter()
+        .filter_map(|ngram| {
+            let positions = seen.remove(ngram)?;
+            if positions.len() < 2 {
+                return None;
+            }
+            Some(Repetition {
+                ngram: ngram.iter().map(|&i| alphabet.char_at(i)).collect(),
+                distances: positions.windows(2).map(|w| w[1] - w[0]).collect(),
+                positions,
+            })
+        })
+        .collect::<Vec<Repetition>>();
+
+    let mut candidates = (2..=max_key_len)
+        .map(|length| KeyLengthCandidate {
+            length,
+            support: repetitions
+                .iter()
+                .flat_map(|r| r.distances.iter())
+                .filter(|&&d| d % length == 0)
+                .count(),
+        })
+        .filter(|c| c.support > 0)
+        .collect::<Vec<KeyLengthCandidate>>();
+    candidates.sort_by(|a, b| b.support.cmp(&a.support).then(b.length.cmp(&a.length)));
+
+    Kasiski {
+        repetitions,
+        candidates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SAMPLE_TEXT;
+    use crate::{NonAlpha, VigenereCipher};
+
+    #[test]
+    fn test_repetitions() {
+        let result = kasiski("ABCDXABCDYABC", &Alphabet::latin(), 4, 6);
+        assert_eq!(
+            vec![Repetition {
+                ngram: "ABCD".to_string(),
+                positions: vec![0, 5],
+                distances: vec![5],
+            }],
+            result.repetitions
+        );
+        assert_eq!(
+            vec![KeyLengthCandidate {
+                length: 5,
+                support: 1
+            }],
+            result.candidates
+        );
+    }
+
+    #[test]
+    fn test_finds_key_length() {
+        let cipher = VigenereCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+        let cipher_text = cipher.encrypt(SAMPLE_TEXT);
+
+        let result = kasiski(&cipher_text, &Alphabet::latin(), 3, 20);
+        assert_eq!(5, result.candidates[0].length);
+    }
+
+    #[test]
+    fn test_short_text() {
+        let result = kasiski("AB", &Alphabet::latin(), 3, 20);
+        assert!(result.repetitions.is_empty());
+        assert!(result.candidates.is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+//! Language models that score how much a text looks like a natural language.
+//!
+//! Key recovery produces several candidate plain texts, and a language model
+//! is what tells the real one apart from the near misses.  The models here
+//! are n-gram models: the score of a text is the sum of the log probabilities
+//! of its overlapping n-grams, so longer n-grams capture more of the
+//! structure of the language at the cost of needing more text to train on.
+//!
+//! The built-in English bigram, trigram and quadgram tables were counted
+//! from 8,513,120 letters of public-domain books from Project Gutenberg: the
+//! collected works of Charles Dickens, *The Adventures of Sherlock Holmes*,
+//! *Alice's Adventures in Wonderland*, *As You Like It* and *Paradise Lost*.
+//! Quadgrams seen fewer than three times are left out.  The tables are
+//! rebuilt by `scripts/english-ngrams.sh`, which names the exact copies used.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::alphabet::Alphabet;
+
+use super::letters;
+use super::solve::ENGLISH_FREQUENCIES;
+
+// Count tables for the built-in bigram, trigram and quadgram models, written
+// by scripts/english-ngrams.sh.
+const ENGLISH_BIGRAMS: &str = include_str!("english_bigrams.txt");
+const ENGLISH_TRIGRAMS: &str = include_str!("english_trigrams.txt");
+const ENGLISH_QUADGRAMS: &str = include_str!("english_quadgrams.txt");
+
+/// Scores how much a text looks like a particular language.
+pub trait LanguageModel {
+    /// Returns the score of `text`.  Higher scores are more like the
+    /// language; the scores of texts of different lengths aren't comparable.
+    fn score(&self, text: &str) -> f64;
+}
+
+/// A language model built from the frequencies of n-grams.
+///
+/// Scores are base 10 log probabilities, and characters outside the model's
+/// alphabet are ignored.  N-grams that never occurred in training are given a
+/// small floor probability rather than zero.
+///
+/// ```
+/// use vigenere_cipher::analysis::{LanguageModel, NgramModel};
+///
+/// let model = NgramModel::english_quadgrams();
+/// assert!(model.score("meet me by the river") > model.score("mzfu nf cz uif sjwfs"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NgramModel {
+    n: usize,
+    alphabet: Alphabet,
+    log_probs: HashMap<Box<[u32]>, f64>,
+    floor: f64,
+}
+
+impl NgramModel {
+    /// Trains a model of `n`-grams over `alphabet` from the text `corpus`.
+    ///
+    /// Lowercase letters are counted as their uppercase form, and anything
+    /// else outside `alphabet` is skipped, so n-grams span word boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn train(n: usize, corpus: &str, alphabet: Alphabet) -> NgramModel {
+        assert!(n > 0, "n-gram length must not be 0");
+
+        let mut counts: HashMap<Box<[u32]>, u64> = HashMap::new();
+        for ngram in letters(corpus, &alphabet).windows(n) {
+            *counts.entry(ngram.into()).or_default() += 1;
+        }
+
+        NgramModel::from_count_map(n, alphabet, counts)
+    }
+
+    /// Trains a model of `n`-grams over `alphabet` from the text in the file
+    /// at `path`, as [`train`](Self::train) does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn from_corpus_file(
+        n: usize,
+        path: impl AsRef<Path>,
+        alphabet: Alphabet,
+    ) -> io::Result<NgramModel> {
+        let corpus = fs::read_to_string(path)?;
+        Ok(NgramModel::train(n, &corpus, alphabet))
+    }
+
+    /// Reads a model from a table of n-gram counts, one per line, such as
+    /// `TION 13168375`.
+    ///
+    /// Blank lines are skipped.  Every n-gram must be the same length and made
+    /// of characters of `alphabet` (in either case); anything else is
+    /// reported as [`io::ErrorKind::InvalidData`].
+    pub fn from_counts(reader: impl BufRead, alphabet: Alphabet) -> io::Result<NgramModel> {
+        let mut n = 0;
+        let mut counts: HashMap<Box<[u32]>, u64> = HashMap::new();
+
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            let invalid = |what: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", line_no + 1, what),
+                )
+            };
+
+            let mut fields = line.split_whitespace();
+            let (ngram, count) = match (fields.next(), fields.next(), fields.next()) {
+                (None, _, _) => continue,
+                (Some(ngram), Some(count), None) => (ngram, count),
+                _ => return Err(invalid("expected an n-gram and a count")),
+            };
+
+            let ngram = ngram
+                .chars()
+                .map(|c| alphabet.index_ignoring_case(c))
+                .collect::<Option<Box<[u32]>>>()
+                .ok_or_else(|| invalid("n-gram has a character outside the alphabet"))?;
+            let count = count
+                .parse::<u64>()
+                .map_err(|_| invalid("count is not a whole number"))?;
+
+            if n == 0 {
+                n = ngram.len();
+            } else if ngram.len() != n {
+                return Err(invalid("n-grams are not all the same length"));
+            }
+
+            let entry = counts.entry(ngram).or_default();
+            *entry = entry.saturating_add(count);
+        }
+
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no n-gram counts found",
+            ));
+        }
+
+        Ok(NgramModel::from_count_map(n, alphabet, counts))
+    }
+
+    /// English letter frequencies over `A`–`Z`.
+    ///
+    /// Like the other built-in models, it is built the first time it is
+    /// asked for and shared from then on.
+    pub fn english_monograms() -> &'static NgramModel {
+        static MODEL: OnceLock<NgramModel> = OnceLock::new();
+        MODEL.get_or_init(|| {
+            let log_probs = ENGLISH_FREQUENCIES
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (Box::from([i as u32]), p.log10()))
+                .collect();
+
+            NgramModel {
+                n: 1,
+                alphabet: Alphabet::latin(),
+                log_probs,
+                floor: ENGLISH_FREQUENCIES
+                    .iter()
+                    .copied()
+                    .fold(f64::INFINITY, f64::min)
+                    .log10(),
+            }
+        })
+    }
+
+    /// English bigram frequencies over `A`–`Z`, from a bundled count table.
+    pub fn english_bigrams() -> &'static NgramModel {
+        static MODEL: OnceLock<NgramModel> = OnceLock::new();
+        MODEL.get_or_init(|| NgramModel::bundled(ENGLISH_BIGRAMS))
+    }
+
+    /// English trigram frequencies over `A`–`Z`, from a bundled count table.
+    pub fn english_trigrams() -> &'static NgramModel {
+        static MODEL: OnceLock<NgramModel> = OnceLock::new();
+        MODEL.get_or_init(|| NgramModel::bundled(ENGLISH_TRIGRAMS))
+    }
+
+    /// English quadgram frequencies over `A`–`Z`, from a bundled count
+    /// table.
+    pub fn english_quadgrams() -> &'static NgramModel {
+        static MODEL: OnceLock<NgramModel> = OnceLock::new();
+        MODEL.get_or_init(|| NgramModel::bundled(ENGLISH_QUADGRAMS))
+    }
+
+    /// Returns the length of the n-grams the model is built from.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the alphabet the model is built over.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    // Reads one of the count tables compiled into the crate.
+    fn bundled(table: &str) -> NgramModel {
+        NgramModel::from_counts(table.as_bytes(), Alphabet::latin())
+            .expect("bundled n-gram tables are well formed")
+    }
+
+    fn from_count_map(
+        n: usize,
+        alphabet: Alphabet,
+        counts: HashMap<Box<[u32]>, u64>,
+    ) -> NgramModel {
+        // Summed as floats, which can't overflow however large the counts.
+        let total = counts
+            .values()
+            .map(|&count| count as f64)
+            .sum::<f64>()
+            .max(1.0);
+        let log_probs = counts
+            .into_iter()
+            .filter(|&(_, count)| count > 0)
+            .map(|(ngram, count)| (ngram, (count as f64 / total).log10()))
+            .collect();
+
+        NgramModel {
+            n,
+            alphabet,
+            log_probs,
+            floor: (0.01 / total).log10(),
+        }
+    }
+}
+
+impl LanguageModel for NgramModel {
+    fn score(&self, text: &str) -> f64 {
+        letters(text, &self.alphabet)
+            .windows(self.n)
+            .map(|ngram| self.log_probs.get(ngram).copied().unwrap_or(self.floor))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SAMPLE_TEXT;
+    use crate::VigenereCipher;
+
+    #[test]
+    fn test_train() {
+        let model = NgramModel::train(2, "abab", Alphabet::latin());
+        assert_eq!(2, model.n());
+        // AB occurs twice and BA once.
+        let ab = (2.0_f64 / 3.0).log10();
+        let ba = (1.0_f64 / 3.0).log10();
+        assert!((model.score("AB") - ab).abs() < 1e-12);
+        assert!((model.score("ABA") - (ab + ba)).abs() < 1e-12);
+        assert_eq!(0.0, model.score("A"));
+        assert!(model.score("BB") < ba);
+    }
+
+    #[test]
+    fn test_english_models_prefer_english() {
+        let shifted = VigenereCipher::new("B")
+            .with_non_alpha(crate::NonAlpha::Preserve)
+            .encrypt(SAMPLE_TEXT);
+
+        for model in [
+            NgramModel::english_monograms(),
+            NgramModel::english_bigrams(),
+            NgramModel::english_trigrams(),
+            NgramModel::english_quadgrams(),
+        ] {
+            assert!(model.score(SAMPLE_TEXT) > model.score(&shifted));
+        }
+    }
+
+    #[test]
+    fn test_english_models_are_built_once() {
+        let quadgrams = NgramModel::english_quadgrams();
+        assert!(std::ptr::eq(quadgrams, NgramModel::english_quadgrams()));
+        assert_eq!(4, quadgrams.n());
+
+        // The commonest n-grams of English score above rare ones.
+        assert!(quadgrams.score("TION") > quadgrams.score("QXZJ"));
+        assert!(
+            NgramModel::english_trigrams().score("THE")
+                > NgramModel::english_trigrams().score("HTE")
+        );
+        assert!(
+            NgramModel::english_bigrams().score("TH") > NgramModel::english_bigrams().score("HT")
+        );
+    }
+
+    #[test]
+    fn test_from_counts() {
+        let table = "TH 30\nHE 20\n\nIN 10\n";
+        let model = NgramModel::from_counts(table.as_bytes(), Alphabet::latin()).unwrap();
+        assert_eq!(2, model.n());
+        assert!((model.score("the") - 0.5_f64.log10() - (1.0_f64 / 3.0).log10()).abs() < 1e-12);
+
+        for bad in ["", "TH\n", "TH 1 2\n", "T1 5\n", "TH x\n", "TH 1\nTHE 1\n"] {
+            let err = NgramModel::from_counts(bad.as_bytes(), Alphabet::latin()).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_from_counts_extremes() {
+        let max = u64::MAX;
+        let table = format!("TH {}\nTH {}\nHE {}\nIN 0\n", max, max, max);
+        let model = NgramModel::from_counts(table.as_bytes(), Alphabet::latin()).unwrap();
+        assert!(model.score("thein").is_finite());
+        // The counts saturate rather than wrapping around to something small.
+        assert_eq!(model.score("TH"), model.score("HE"));
+        // A count of 0 is the same as the n-gram never being seen.
+        assert_eq!(model.floor, model.score("IN"));
+    }
+
+    #[test]
+    fn test_from_corpus_file() {
+        let path = std::env::temp_dir().join(format!("vigenere-corpus-{}", std::process::id()));
+        fs::write(&path, "abab").unwrap();
+        let model = NgramModel::from_corpus_file(2, &path, Alphabet::latin()).unwrap();
+        assert_eq!(NgramModel::train(2, "abab", Alphabet::latin()), model);
+        fs::remove_file(&path).unwrap();
+
+        assert!(NgramModel::from_corpus_file(2, &path, Alphabet::latin()).is_err());
+    }
+}
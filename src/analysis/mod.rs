@@ -0,0 +1,59 @@
+//! Cryptanalysis of cipher text produced by [`VigenereCipher`], and of
+//! repeating-key XOR produced by [`ByteCipher`].
+//!
+//! The analyses only look at characters of the alphabet they are given:
+//! lowercase letters are folded to uppercase and anything else is skipped.
+//! Cipher text produced under [`NonAlpha::Preserve`] can be analysed as is,
+//! since only its letters used up the key.  Cipher text produced under
+//! [`NonAlpha::PreserveAndAdvance`] is a different matter: every character
+//! used up a key position there, so once the other characters are skipped,
+//! the letters no longer repeat with the period of the key.
+//!
+//! [`VigenereCipher`]: crate::VigenereCipher
+//! [`ByteCipher`]: crate::ByteCipher
+//! [`NonAlpha::Preserve`]: crate::NonAlpha::Preserve
+//! [`NonAlpha::PreserveAndAdvance`]: crate::NonAlpha::PreserveAndAdvance
+
+pub mod coincidence;
+pub mod kasiski;
+pub mod language;
+pub mod solve;
+pub mod xor;
+
+pub use coincidence::{
+    column_coincidences, friedman, index_of_coincidence, rank_periods, PeriodScore, ENGLISH_IC,
+};
+pub use kasiski::{kasiski, Kasiski, KeyLengthCandidate, Repetition};
+pub use language::{LanguageModel, NgramModel};
+pub use solve::{break_vigenere, break_vigenere_with, KeyCandidate, ENGLISH_FREQUENCIES};
+pub use xor::{
+    break_repeating_xor, hamming_distance, rank_xor_key_sizes, KeySizeScore, XorKeyCandidate,
+};
+
+use crate::alphabet::Alphabet;
+
+// Converts every character of `text` that is in `alphabet` (ignoring case)
+// to its position, dropping the rest.
+pub(crate) fn letters(text: &str, alphabet: &Alphabet) -> Vec<u32> {
+    text.chars()
+        .filter_map(|c| alphabet.fold_index(c).map(|(i, _)| i))
+        .collect()
+}
+
+// An English passage long enough for the statistics to settle down, shared
+// by the tests of each analysis.
+#[cfg(test)]
+pub(crate) const SAMPLE_TEXT: &str = "\
+It was the best of times, it was the worst of times, it was the age of \
+wisdom, it was the age of foolishness, it was the epoch of belief, it was \
+the epoch of incredulity, it was the season of Light, it was the season of \
+Darkness, it was the spring of hope, it was the winter of despair, we had \
+everything before us, we had nothing before us, we were all going direct to \
+Heaven, we were all going direct the other way. In short, the period was so \
+far like the present period, that some of its noisiest authorities insisted \
+on its being received, for good or for evil, in the superlative degree of \
+comparison only. There were a king with a large jaw and a queen with a \
+plain face, on the throne of England; there were a king with a large jaw \
+and a queen with a fair face, on the throne of France. In both countries it \
+was clearer than crystal to the lords of the State preserves of loaves and \
+fishes, that things in general were settled for ever.";
@@ -0,0 +1,264 @@
+//! Ciphertext-only key recovery for English plain text.
+//!
+//! Once the key length is known, each column of the cipher text is a Caesar
+//! cipher: every character in it was shifted by the same key character.  Each
+//! column is solved on its own by trying every shift and keeping the one whose
+//! letter frequencies are closest to English, measured by the chi-squared
+//! statistic.  The candidate keys are then ranked by how English their whole
+//! plain text reads according to a [`LanguageModel`].
+
+use crate::alphabet::Alphabet;
+use crate::cipher::{NonAlpha, VigenereCipher};
+use crate::key::minimal_key;
+
+use super::coincidence::{columns, rank_periods};
+use super::language::{LanguageModel, NgramModel};
+use super::letters;
+
+/// How often each of the letters `A`–`Z` occurs in English text.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+// The longest key that is tried.
+const MAX_PERIOD: usize = 20;
+
+// How many of the best scoring key lengths are solved.
+const PERIODS_TRIED: usize = 4;
+
+/// A key recovered by [`break_vigenere`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyCandidate {
+    /// The recovered key.
+    pub key: String,
+    /// The cipher text decrypted with `key`.
+    pub plain_text: String,
+    /// The chi-squared statistic of the letters of `plain_text` against
+    /// English; lower is more English-like.
+    pub chi_squared: f64,
+    /// The score of `plain_text` under the language model the candidates
+    /// were ranked by; higher is more English-like.
+    pub score: f64,
+    /// This candidate's share, between 0 and 1, of the likelihood of all the
+    /// candidates that were considered.
+    pub confidence: f64,
+}
+
+// The chi-squared statistic of letters shifted back by `shift`, against
+// English letter frequencies.
+fn chi_squared(text: &[u32], shift: u32) -> f64 {
+    let mut counts = [0usize; 26];
+    for &i in text {
+        counts[((i + 26 - shift) % 26) as usize] += 1;
+    }
+
+    let n = text.len() as f64;
+    counts
+        .iter()
+        .zip(ENGLISH_FREQUENCIES.iter())
+        .map(|(&observed, &frequency)| {
+            let expected = n * frequency;
+            (observed as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+// Every shift of `column` with its chi-squared statistic, best first.
+fn rank_shifts(column: &[u32]) -> Vec<(u32, f64)> {
+    let mut shifts = (0..26)
+        .map(|shift| (shift, chi_squared(column, shift)))
+        .collect::<Vec<(u32, f64)>>();
+    shifts.sort_by(|a, b| a.1.total_cmp(&b.1));
+    shifts
+}
+
+// The chi-squared statistic of the whole text decrypted with `key`.
+fn score_key(text: &[u32], key: &[u32]) -> f64 {
+    columns(text, key.len())
+        .iter()
+        .zip(key)
+        .map(|(column, &shift)| chi_squared(column, shift))
+        .sum()
+}
+
+/// Recovers the key of `cipher_text`, assuming the plain text is English,
+/// and returns up to `top_n` candidates, most likely first.
+///
+/// Key lengths up to 20 are considered, and the candidates are ranked by
+/// [`NgramModel::english_quadgrams`].  The cipher text may have been produced
+/// with [`NonAlpha::Reject`] or [`NonAlpha::Preserve`], and each `plain_text`
+/// is decrypted with [`NonAlpha::Preserve`] so that any punctuation and case
+/// comes back as it was.
+///
+/// ```
+/// use vigenere_cipher::analysis::break_vigenere;
+/// use vigenere_cipher::{NonAlpha, VigenereCipher};
+///
+/// let plain_text = "It is a truth universally acknowledged, that a single man in \
+///     possession of a good fortune, must be in want of a wife. However little \
+///     known the feelings or views of such a man may be on his first entering \
+///     a neighbourhood, this truth is so well fixed in the minds of the \
+///     surrounding families, that he is considered the rightful property of \
+///     some one or other of their daughters.";
+/// let cipher = VigenereCipher::new("AUSTEN").with_non_alpha(NonAlpha::Preserve);
+///
+/// let candidates = break_vigenere(&cipher.encrypt(plain_text), 3);
+/// assert_eq!("AUSTEN", candidates[0].key);
+/// assert_eq!(plain_text, candidates[0].plain_text);
+/// ```
+pub fn break_vigenere(cipher_text: &str, top_n: usize) -> Vec<KeyCandidate> {
+    break_vigenere_with(cipher_text, top_n, NgramModel::english_quadgrams())
+}
+
+/// Recovers the key of `cipher_text` like [`break_vigenere`], but ranks the
+/// candidates by `model` instead of the built-in English quadgrams.
+///
+/// The columns are still solved against English letter frequencies, so
+/// `model` is best used to tell apart candidates for English-like languages,
+/// or to rank with a model trained on a larger corpus.
+pub fn break_vigenere_with(
+    cipher_text: &str,
+    top_n: usize,
+    model: &dyn LanguageModel,
+) -> Vec<KeyCandidate> {
+    let alphabet = Alphabet::latin();
+    let text = letters(cipher_text, &alphabet);
+    if text.is_empty() || top_n == 0 {
+        return Vec::new();
+    }
+
+    let mut candidates = candidate_keys(cipher_text, &text)
+        .into_iter()
+        .map(|key| {
+            let chi_squared = score_key(&text, &key);
+            let key = key.iter().map(|&i| alphabet.char_at(i)).collect::<String>();
+            let plain_text = VigenereCipher::new(&key)
+                .with_non_alpha(NonAlpha::Preserve)
+                .decrypt(cipher_text);
+            KeyCandidate {
+                score: model.score(&plain_text),
+                key,
+                plain_text,
+                chi_squared,
+                confidence: 0.0,
+            }
+        })
+        .collect::<Vec<KeyCandidate>>();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    // The scores are base 10 log likelihoods, so this turns them into
+    // relative likelihoods.  Shifting by the best one keeps the exponentials
+    // from underflowing.
+    let best_score = candidates[0].score;
+    let likelihoods = candidates
+        .iter()
+        .map(|c| 10f64.powf(c.score - best_score))
+        .collect::<Vec<f64>>();
+    let total = likelihoods.iter().sum::<f64>();
+    for (candidate, likelihood) in candidates.iter_mut().zip(likelihoods) {
+        candidate.confidence = likelihood / total;
+    }
+
+    candidates.truncate(top_n);
+    candidates
+}
+
+// Solves the columns for the most likely key lengths, returning the best key
+// for each along with its runners-up.
+fn candidate_keys(cipher_text: &str, text: &[u32]) -> Vec<Vec<u32>> {
+    let max_period = MAX_PERIOD.min(text.len() / 2).max(1);
+    let mut keys: Vec<Vec<u32>> = Vec::new();
+
+    for score in rank_periods(cipher_text, &Alphabet::latin(), max_period)
+        .iter()
+        .take(PERIODS_TRIED)
+    {
+        let ranked = columns(text, score.period)
+            .iter()
+            .map(|column| rank_shifts(column))
+            .collect::<Vec<Vec<(u32, f64)>>>();
+        let best = ranked
+            .iter()
+            .map(|shifts| shifts[0].0)
+            .collect::<Vec<u32>>();
+
+        // The runners-up differ from the best key in a single column, using
+        // that column's second best shift.
+        let mut variants = vec![best.clone()];
+        for (column, shifts) in ranked.iter().enumerate() {
+            let mut variant = best.clone();
+            variant[column] = shifts[1].0;
+            variants.push(variant);
+        }
+
+        for key in variants {
+            let key = minimal_key(&key).to_vec();
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SAMPLE_TEXT;
+
+    #[test]
+    fn test_chi_squared_prefers_english() {
+        let text = letters("THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG", &Alphabet::latin());
+        assert_eq!(0, rank_shifts(&text)[0].0);
+    }
+
+    #[test]
+    fn test_break_vigenere() {
+        for key in ["LEMON", "DUH", "CRYPTOGRAPHY"] {
+            let cipher_text = VigenereCipher::new(key)
+                .with_non_alpha(NonAlpha::Preserve)
+                .encrypt(SAMPLE_TEXT);
+
+            let candidates = break_vigenere(&cipher_text, 5);
+            assert_eq!(5, candidates.len());
+            assert_eq!(key, candidates[0].key);
+            assert_eq!(SAMPLE_TEXT, candidates[0].plain_text);
+            assert!(candidates[0].confidence > 0.9, "{:?}", candidates[0]);
+            assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+        }
+    }
+
+    #[test]
+    fn test_break_vigenere_strict_cipher_text() {
+        let plain_text = letters(SAMPLE_TEXT, &Alphabet::latin())
+            .iter()
+            .map(|&i| Alphabet::latin().char_at(i))
+            .collect::<String>();
+        let cipher_text = VigenereCipher::new("KEY").encrypt(&plain_text);
+
+        let candidates = break_vigenere(&cipher_text, 1);
+        assert_eq!("KEY", candidates[0].key);
+        assert_eq!(plain_text, candidates[0].plain_text);
+    }
+
+    #[test]
+    fn test_break_vigenere_empty() {
+        assert!(break_vigenere("", 3).is_empty());
+        assert!(break_vigenere("1234", 3).is_empty());
+    }
+
+    #[test]
+    fn test_break_vigenere_with_model() {
+        let cipher_text = VigenereCipher::new("LEMON")
+            .with_non_alpha(NonAlpha::Preserve)
+            .encrypt(SAMPLE_TEXT);
+
+        let model = NgramModel::english_monograms();
+        let candidates = break_vigenere_with(&cipher_text, 2, model);
+        assert_eq!("LEMON", candidates[0].key);
+        assert_eq!(model.score(SAMPLE_TEXT), candidates[0].score);
+    }
+}
@@ -0,0 +1,238 @@
+//! Breaking repeating-key XOR, as produced by [`ByteCipher`] with
+//! [`ByteOp::Xor`].
+//!
+//! The key size is found from the Hamming distance between blocks of the
+//! cipher text: two blocks of English XORed with the same key differ in
+//! fewer bits than two blocks XORed with different parts of it.  With the
+//! key size known, each column is a single-byte XOR, solved by trying every
+//! byte and keeping the one whose output looks most like English text.
+//!
+//! [`ByteCipher`]: crate::ByteCipher
+//! [`ByteOp::Xor`]: crate::ByteOp::Xor
+
+use crate::key::minimal_key;
+
+use super::solve::ENGLISH_FREQUENCIES;
+
+// The longest key that is tried.
+const MAX_KEY_SIZE: usize = 40;
+
+// How many of the best scoring key sizes are solved.
+const KEY_SIZES_TRIED: usize = 4;
+
+// How often a space occurs in English text, relative to the letter
+// frequencies.
+const SPACE_FREQUENCY: f64 = 0.19;
+
+/// Returns the number of bits that differ between `a` and `b`.
+///
+/// ```
+/// use vigenere_cipher::analysis::hamming_distance;
+///
+/// assert_eq!(37, hamming_distance(b"this is a test", b"wokka wokka!!!"));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `a` and `b` are not the same length.
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    assert_eq!(a.len(), b.len(), "hamming distance of unequal lengths");
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+/// A key size and how well the cipher text lines up at it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeySizeScore {
+    /// The key size, in bytes.
+    pub size: usize,
+    /// The mean Hamming distance between consecutive blocks of `size` bytes,
+    /// per byte; lower means the key size is more likely.
+    pub distance: f64,
+}
+
+/// Scores every key size from 1 to `max_key_size` and returns them most
+/// likely first.
+///
+/// Key sizes that don't leave at least two whole blocks of cipher text are
+/// skipped.
+pub fn rank_xor_key_sizes(cipher_text: &[u8], max_key_size: usize) -> Vec<KeySizeScore> {
+    let mut scores = (1..=max_key_size)
+        .filter(|&size| cipher_text.len() >= size * 2)
+        .map(|size| {
+            let blocks = cipher_text.chunks_exact(size).collect::<Vec<&[u8]>>();
+            let total = blocks
+                .windows(2)
+                .map(|pair| hamming_distance(pair[0], pair[1]) as f64)
+                .sum::<f64>();
+            KeySizeScore {
+                size,
+                distance: total / ((blocks.len() - 1) * size) as f64,
+            }
+        })
+        .collect::<Vec<KeySizeScore>>();
+    scores.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    scores
+}
+
+/// A key recovered by [`break_repeating_xor`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct XorKeyCandidate {
+    /// The recovered key.
+    pub key: Vec<u8>,
+    /// The cipher text decrypted with `key`.
+    pub plain_text: Vec<u8>,
+    /// How much `plain_text` looks like English text, per byte; higher is
+    /// more English-like.
+    pub score: f64,
+}
+
+// How much each byte of a key costs a candidate when they are ranked.  A
+// longer key has more columns to fit to the text, so a multiple of the right
+// key size can score a little higher than the right one by fitting to noise.
+const KEY_BYTE_PENALTY: f64 = 0.01;
+
+// How often a letter is capitalized in English text.  XORing with 0x20 swaps
+// the case of a letter, so without this a key byte and its case-swapped
+// counterpart would score the same.
+const CAPITAL_FREQUENCY: f64 = 0.1;
+
+// How much a byte looks like part of English text: letters and spaces score
+// their frequency, other printable characters nothing and anything else is
+// penalized.
+fn byte_score(b: u8) -> f64 {
+    match b {
+        b'a'..=b'z' => ENGLISH_FREQUENCIES[(b - b'a') as usize],
+        b'A'..=b'Z' => ENGLISH_FREQUENCIES[(b - b'A') as usize] * CAPITAL_FREQUENCY,
+        b' ' => SPACE_FREQUENCY,
+        b'\n' | b'\r' | b'\t' | b'!'..=b'~' => 0.0,
+        _ => -1.0,
+    }
+}
+
+fn text_score(text: &[u8]) -> f64 {
+    text.iter().map(|&b| byte_score(b)).sum::<f64>() / text.len() as f64
+}
+
+// The key byte that makes `column` look most like English.
+fn solve_column(column: &[u8]) -> u8 {
+    (0..=255)
+        .max_by(|&a: &u8, &b: &u8| {
+            let score = |k: u8| column.iter().map(|&c| byte_score(c ^ k)).sum::<f64>();
+            score(a).total_cmp(&score(b))
+        })
+        .unwrap_or(0)
+}
+
+/// Recovers the key of `cipher_text`, assuming the plain text is English,
+/// and returns up to `top_n` candidates, most likely first.
+///
+/// Key sizes up to 40 bytes are considered.  The candidates are ranked by
+/// their [`score`](XorKeyCandidate::score), less a small penalty for the
+/// length of the key, so that a key isn't beaten by itself repeated.
+///
+/// ```
+/// use vigenere_cipher::analysis::break_repeating_xor;
+/// use vigenere_cipher::{ByteCipher, ByteOp};
+///
+/// let plain_text = b"It is a truth universally acknowledged, that a single man in \
+///     possession of a good fortune, must be in want of a wife. However little \
+///     known the feelings or views of such a man may be on his first entering \
+///     a neighbourhood, this truth is so well fixed in the minds of the \
+///     surrounding families, that he is considered the rightful property of \
+///     some one or other of their daughters.";
+/// let cipher = ByteCipher::new(b"Austen").with_op(ByteOp::Xor);
+///
+/// let candidates = break_repeating_xor(&cipher.encrypt(plain_text), 3);
+/// assert_eq!(b"Austen", &candidates[0].key[..]);
+/// assert_eq!(&plain_text[..], &candidates[0].plain_text[..]);
+/// ```
+pub fn break_repeating_xor(cipher_text: &[u8], top_n: usize) -> Vec<XorKeyCandidate> {
+    if cipher_text.is_empty() || top_n == 0 {
+        return Vec::new();
+    }
+
+    let max_key_size = MAX_KEY_SIZE.min(cipher_text.len() / 2).max(1);
+    let mut candidates: Vec<XorKeyCandidate> = Vec::new();
+    for score in rank_xor_key_sizes(cipher_text, max_key_size)
+        .iter()
+        .take(KEY_SIZES_TRIED)
+    {
+        let key = (0..score.size)
+            .map(|column| {
+                let column = cipher_text
+                    .iter()
+                    .skip(column)
+                    .step_by(score.size)
+                    .copied()
+                    .collect::<Vec<u8>>();
+                solve_column(&column)
+            })
+            .collect::<Vec<u8>>();
+        let key = minimal_key(&key).to_vec();
+        if candidates.iter().any(|c| c.key == key) {
+            continue;
+        }
+
+        let plain_text = cipher_text
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(c, k)| c ^ k)
+            .collect::<Vec<u8>>();
+        candidates.push(XorKeyCandidate {
+            score: text_score(&plain_text),
+            key,
+            plain_text,
+        });
+    }
+    let ranking = |c: &XorKeyCandidate| {
+        c.score - KEY_BYTE_PENALTY * c.key.len() as f64 / cipher_text.len() as f64
+    };
+    candidates.sort_by(|a, b| ranking(b).total_cmp(&ranking(a)));
+
+    candidates.truncate(top_n);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SAMPLE_TEXT;
+    use crate::{ByteCipher, ByteOp};
+
+    #[test]
+    fn test_solve_column() {
+        let column = b"the quick brown fox"
+            .iter()
+            .map(|b| b ^ 0x5a)
+            .collect::<Vec<u8>>();
+        assert_eq!(0x5a, solve_column(&column));
+    }
+
+    #[test]
+    fn test_rank_xor_key_sizes() {
+        let cipher_text = ByteCipher::new(b"SECRET")
+            .with_op(ByteOp::Xor)
+            .encrypt(SAMPLE_TEXT.as_bytes());
+        let scores = rank_xor_key_sizes(&cipher_text, 40);
+        assert_eq!(0, scores[0].size % 6, "{:?}", &scores[..4]);
+        assert_eq!(1, rank_xor_key_sizes(b"abc", 2).len());
+    }
+
+    #[test]
+    fn test_break_repeating_xor() {
+        for key in [&b"ICE"[..], b"LEMON", b"\x01\x80\xfe\x10binary\x00"] {
+            let cipher_text = ByteCipher::new(key)
+                .with_op(ByteOp::Xor)
+                .encrypt(SAMPLE_TEXT.as_bytes());
+
+            let candidates = break_repeating_xor(&cipher_text, 2);
+            assert_eq!(key, &candidates[0].key[..]);
+            assert_eq!(SAMPLE_TEXT.as_bytes(), &candidates[0].plain_text[..]);
+        }
+    }
+
+    #[test]
+    fn test_break_repeating_xor_empty() {
+        assert!(break_repeating_xor(b"", 3).is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! The autokey variant of the Vigenère cipher.
+//!
+//! Instead of repeating, the key is a short primer followed by the plain text
+//! itself: once the primer runs out, each letter is enciphered by the plain
+//! text letter that came a primer's length before it.  Decryption recovers the
+//! plain text a letter at a time and feeds it back into the key.
+
+use std::collections::VecDeque;
+
+use crate::alphabet::Alphabet;
+use crate::cipher::{reverse_rotate_index, rotate_index, transform, Keystream, NonAlpha};
+use crate::error::CipherError;
+use crate::key::Key;
+
+// The autokey keystream: the key characters still to be used, which the
+// plain text is appended to as it goes by.
+struct AutoKey {
+    pending: VecDeque<u32>,
+    n: u32,
+    decrypt: bool,
+}
+
+impl Keystream for AutoKey {
+    fn letter(&mut self, i: u32) -> u32 {
+        // A letter is enciphered for every key character taken, and one is
+        // put back in its place, so there is always another to take.
+        let shift_amt = self.pending.pop_front().unwrap_or(0) % self.n;
+
+        if self.decrypt {
+            let plain = reverse_rotate_index(i, shift_amt, self.n);
+            self.pending.push_back(plain);
+            plain
+        } else {
+            self.pending.push_back(i);
+            rotate_index(i, shift_amt, self.n)
+        }
+    }
+
+    fn advance(&mut self) {
+        // There is no plain text letter to feed back, so the skipped key
+        // character goes to the back of the queue instead.
+        if let Some(shift_amt) = self.pending.pop_front() {
+            self.pending.push_back(shift_amt);
+        }
+    }
+}
+
+/// An autokey Vigenère cipher bound to a primer.
+///
+/// Every character of the primer must be in the cipher's [`Alphabet`], which
+/// is `A`–`Z` unless the cipher was created with [`new_in`](Self::new_in).
+/// Text is handled the same way as by [`VigenereCipher`](crate::VigenereCipher),
+/// including [`with_non_alpha`](Self::with_non_alpha); under
+/// [`NonAlpha::PreserveAndAdvance`] a skipped key character is used again
+/// after the plain text that has been fed back so far.
+///
+/// ```
+/// use vigenere_cipher::AutokeyCipher;
+///
+/// let cipher = AutokeyCipher::new("QUEENLY");
+/// assert_eq!("QNXEPVYTWTWP", cipher.encrypt("ATTACKATDAWN"));
+/// assert_eq!("ATTACKATDAWN", cipher.decrypt("QNXEPVYTWTWP"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutokeyCipher {
+    primer: Key,
+    alphabet: Alphabet,
+    non_alpha: NonAlpha,
+}
+
+impl AutokeyCipher {
+    /// Creates a cipher whose key starts with `primer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `primer` is empty or contains anything other than `A`–`Z`.
+    /// Use [`try_new`](Self::try_new) to handle an invalid primer instead.
+    pub fn new(primer: &str) -> AutokeyCipher {
+        AutokeyCipher::try_new(primer).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher whose key starts with `primer`, or reports why
+    /// `primer` can't be used.
+    pub fn try_new(primer: &str) -> Result<AutokeyCipher, CipherError> {
+        AutokeyCipher::try_new_in(primer, Alphabet::default())
+    }
+
+    /// Creates a cipher that rotates characters within `alphabet` instead of
+    /// `A`–`Z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `primer` is empty or contains a character outside
+    /// `alphabet`.  Use [`try_new_in`](Self::try_new_in) to handle an invalid
+    /// primer instead.
+    pub fn new_in(primer: &str, alphabet: Alphabet) -> AutokeyCipher {
+        AutokeyCipher::try_new_in(primer, alphabet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that rotates characters within `alphabet`, or
+    /// reports why `primer` can't be used with it.
+    pub fn try_new_in(primer: &str, alphabet: Alphabet) -> Result<AutokeyCipher, CipherError> {
+        let primer = Key::parse(primer, &alphabet)?;
+        Ok(AutokeyCipher::from_key(primer, alphabet))
+    }
+
+    /// Creates a cipher from an already validated primer.
+    pub fn from_key(primer: Key, alphabet: Alphabet) -> AutokeyCipher {
+        AutokeyCipher {
+            primer,
+            alphabet,
+            non_alpha: NonAlpha::default(),
+        }
+    }
+
+    /// Sets how characters outside the alphabet are handled.
+    pub fn with_non_alpha(mut self, non_alpha: NonAlpha) -> AutokeyCipher {
+        self.non_alpha = non_alpha;
+        self
+    }
+
+    /// Returns how characters outside the alphabet are handled.
+    pub fn non_alpha(&self) -> NonAlpha {
+        self.non_alpha
+    }
+
+    /// Returns the primer this cipher was created with.
+    pub fn primer(&self) -> &str {
+        self.primer.as_str()
+    }
+
+    /// Returns the alphabet this cipher rotates characters within.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Encrypts `plain_text`, returning the cipher text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plain_text` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn encrypt(&self, plain_text: &str) -> String {
+        self.try_encrypt(plain_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Decrypts `cipher_text`, returning the plain text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cipher_text` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn decrypt(&self, cipher_text: &str) -> String {
+        self.try_decrypt(cipher_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts `plain_text`, or reports the first character that can't be
+    /// handled.
+    pub fn try_encrypt(&self, plain_text: &str) -> Result<String, CipherError> {
+        self.transform(plain_text, false)
+    }
+
+    /// Decrypts `cipher_text`, or reports the first character that can't be
+    /// handled.
+    pub fn try_decrypt(&self, cipher_text: &str) -> Result<String, CipherError> {
+        self.transform(cipher_text, true)
+    }
+
+    fn transform(&self, val: &str, decrypt: bool) -> Result<String, CipherError> {
+        let mut keystream = AutoKey {
+            pending: self.primer.shifts().iter().copied().collect(),
+            n: self.alphabet.size() as u32,
+            decrypt,
+        };
+        transform(&self.alphabet, self.non_alpha, val, &mut keystream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_vectors() {
+        // From the description of the autokey cipher on Wikipedia.
+        let cipher = AutokeyCipher::new("QUEENLY");
+        assert_eq!("QNXEPVYTWTWP", cipher.encrypt("ATTACKATDAWN"));
+        assert_eq!("ATTACKATDAWN", cipher.decrypt("QNXEPVYTWTWP"));
+
+        // From Practical Cryptography's autokey page.
+        let cipher = AutokeyCipher::new("FORTIFICATION");
+        let plain_text = "DEFENDTHEEASTWALLOFTHECASTLE";
+        let cipher_text = "ISWXVIBJEXIGGZEQPBIMOIGAKMHE";
+        assert_eq!(cipher_text, cipher.encrypt(plain_text));
+        assert_eq!(plain_text, cipher.decrypt(cipher_text));
+    }
+
+    #[test]
+    fn test_non_alpha() {
+        let cipher = AutokeyCipher::new("QUEENLY").with_non_alpha(NonAlpha::Preserve);
+        assert_eq!("Qnxepv yt wtwp!", cipher.encrypt("Attack at dawn!"));
+        assert_eq!("Attack at dawn!", cipher.decrypt("Qnxepv yt wtwp!"));
+
+        let cipher = cipher.with_non_alpha(NonAlpha::PreserveAndAdvance);
+        let cipher_text = cipher.encrypt("Attack at dawn, attack at dusk!");
+        assert_eq!(
+            "Attack at dawn, attack at dusk!",
+            cipher.decrypt(&cipher_text)
+        );
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        assert_eq!(Err(CipherError::EmptyKey), AutokeyCipher::try_new(""));
+        assert_eq!(
+            Err(CipherError::InvalidInputChar { pos: 4, ch: '1' }),
+            AutokeyCipher::new("KEY").try_encrypt("ABCD1")
+        );
+    }
+
+    #[test]
+    fn test_other_alphabet() {
+        let cipher = AutokeyCipher::new_in("КЛЮЧ", Alphabet::cyrillic());
+        let cipher_text = cipher.encrypt("ПРИВЕТМИР");
+        assert_eq!("ПРИВЕТМИР", cipher.decrypt(&cipher_text));
+    }
+}
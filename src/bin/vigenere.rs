@@ -0,0 +1,287 @@
+// Command line front end for the library: encrypts or decrypts a file (or
+// stdin) with a key given on the command line or read from a file.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use vigenere_cipher::{Alphabet, CipherError, DigitMapping, KeyBuilder, NonAlpha, VigenereCipher};
+
+const USAGE: &str = "\
+usage: vigenere <encrypt|decrypt> (--key KEY | --key-file PATH | --passphrase TEXT) [options]
+
+options:
+  -k, --key KEY            key to encrypt or decrypt with
+      --key-file PATH      read the key from PATH (surrounding whitespace is ignored)
+  -p, --passphrase TEXT    derive the key from TEXT, dropping anything that isn't
+                           a letter of the alphabet once accents are removed
+      --digits MODE        what --passphrase does with digits: drop (default) or
+                           letters (0 is the first letter, 1 the second, ...)
+  -i, --input PATH         read from PATH instead of stdin
+  -o, --output PATH        write to PATH instead of stdout
+  -a, --alphabet NAME      latin (default), alphanumeric, printable, cyrillic or greek
+  -n, --non-alpha MODE     reject (default), preserve or advance
+  -h, --help               print this message
+
+A single trailing line ending on the input is kept as is and not enciphered.
+
+exit status:
+  0  success
+  1  the input or output could not be read or written
+  2  the command line was invalid
+  3  the key was invalid
+  4  the input was not UTF-8 or had a character that could not be enciphered
+";
+
+// Exit statuses, as listed in USAGE.
+const EXIT_IO: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_KEY: u8 = 3;
+const EXIT_INPUT: u8 = 4;
+
+enum Command {
+    Encrypt,
+    Decrypt,
+}
+
+enum KeySource {
+    Literal(String),
+    File(String),
+    Passphrase(String),
+}
+
+struct Options {
+    command: Command,
+    key: KeySource,
+    input: Option<String>,
+    output: Option<String>,
+    alphabet: Alphabet,
+    non_alpha: NonAlpha,
+    digits: DigitMapping,
+}
+
+// A reason to stop, along with the exit status to report it with.
+struct Failure {
+    status: u8,
+    message: String,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Failure {
+        Failure {
+            status: EXIT_USAGE,
+            message: message.into(),
+        }
+    }
+
+    fn io(what: &str, path: Option<&str>, e: io::Error) -> Failure {
+        let message = match path {
+            Some(path) => format!("could not {} {}: {}", what, path, e),
+            None => format!("could not {}: {}", what, e),
+        };
+        Failure {
+            status: EXIT_IO,
+            message,
+        }
+    }
+}
+
+fn parse_alphabet(name: &str) -> Result<Alphabet, Failure> {
+    match name {
+        "latin" => Ok(Alphabet::latin()),
+        "alphanumeric" => Ok(Alphabet::alphanumeric()),
+        "printable" => Ok(Alphabet::printable_ascii()),
+        "cyrillic" => Ok(Alphabet::cyrillic()),
+        "greek" => Ok(Alphabet::greek()),
+        _ => Err(Failure::usage(format!("unknown alphabet {:?}", name))),
+    }
+}
+
+fn parse_non_alpha(mode: &str) -> Result<NonAlpha, Failure> {
+    match mode {
+        "reject" => Ok(NonAlpha::Reject),
+        "preserve" => Ok(NonAlpha::Preserve),
+        "advance" => Ok(NonAlpha::PreserveAndAdvance),
+        _ => Err(Failure::usage(format!("unknown non-alpha mode {:?}", mode))),
+    }
+}
+
+fn parse_digits(mode: &str) -> Result<DigitMapping, Failure> {
+    match mode {
+        "drop" => Ok(DigitMapping::Drop),
+        "letters" => Ok(DigitMapping::Letters),
+        _ => Err(Failure::usage(format!("unknown digit mode {:?}", mode))),
+    }
+}
+
+// Returns None when help was asked for.
+fn parse_args(args: &[String]) -> Result<Option<Options>, Failure> {
+    let mut command = None;
+    let mut key = None;
+    let mut input = None;
+    let mut output = None;
+    let mut alphabet = Alphabet::default();
+    let mut non_alpha = NonAlpha::default();
+    let mut digits = DigitMapping::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(None);
+        }
+
+        if !arg.starts_with('-') {
+            if command.is_some() {
+                return Err(Failure::usage(format!("unexpected argument {:?}", arg)));
+            }
+            command = match arg.as_str() {
+                "encrypt" => Some(Command::Encrypt),
+                "decrypt" => Some(Command::Decrypt),
+                _ => return Err(Failure::usage(format!("unknown command {:?}", arg))),
+            };
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| Failure::usage(format!("{} needs a value", arg)))?
+            .clone();
+
+        match arg.as_str() {
+            "-k" | "--key" => key = Some(KeySource::Literal(value)),
+            "--key-file" => key = Some(KeySource::File(value)),
+            "-p" | "--passphrase" => key = Some(KeySource::Passphrase(value)),
+            "-i" | "--input" => input = Some(value),
+            "-o" | "--output" => output = Some(value),
+            "-a" | "--alphabet" => alphabet = parse_alphabet(&value)?,
+            "-n" | "--non-alpha" => non_alpha = parse_non_alpha(&value)?,
+            "--digits" => digits = parse_digits(&value)?,
+            _ => return Err(Failure::usage(format!("unknown option {:?}", arg))),
+        }
+    }
+
+    Ok(Some(Options {
+        command: command.ok_or_else(|| Failure::usage("missing command"))?,
+        key: key.ok_or_else(|| Failure::usage("missing --key, --key-file or --passphrase"))?,
+        input,
+        output,
+        alphabet,
+        non_alpha,
+        digits,
+    }))
+}
+
+fn run(options: Options) -> Result<(), Failure> {
+    let key = match options.key {
+        KeySource::Literal(key) => key,
+        KeySource::File(path) => fs::read_to_string(&path)
+            .map_err(|e| Failure::io("read key file", Some(&path), e))?
+            .trim()
+            .to_string(),
+        KeySource::Passphrase(passphrase) => {
+            let derived = KeyBuilder::new_in(options.alphabet.clone())
+                .with_digits(options.digits)
+                .derive(&passphrase)
+                .map_err(|e| Failure {
+                    status: EXIT_KEY,
+                    message: format!("no key in passphrase: {}", e),
+                })?;
+            if !derived.removed.is_empty() {
+                let removed = derived
+                    .removed
+                    .iter()
+                    .map(|r| format!("{:?}", r.ch))
+                    .collect::<Vec<String>>();
+                eprintln!("vigenere: removed from passphrase: {}", removed.join(" "));
+            }
+            if !derived.transliterated.is_empty() {
+                let transliterated = derived
+                    .transliterated
+                    .iter()
+                    .map(|t| format!("{:?} as {}", t.ch, t.replacement))
+                    .collect::<Vec<String>>();
+                eprintln!(
+                    "vigenere: transliterated in passphrase: {}",
+                    transliterated.join(", ")
+                );
+            }
+            derived.key.as_str().to_string()
+        }
+    };
+
+    let cipher = VigenereCipher::try_new_in(&key, options.alphabet)
+        .map_err(|e| Failure {
+            status: EXIT_KEY,
+            message: e.to_string(),
+        })?
+        .with_non_alpha(options.non_alpha);
+
+    let text = match &options.input {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text)
+        }
+    }
+    .map_err(|e| match e.kind() {
+        // The input could be read, but isn't text that can be enciphered.
+        io::ErrorKind::InvalidData => Failure {
+            status: EXIT_INPUT,
+            message: match &options.input {
+                Some(path) => format!("{} is not valid UTF-8", path),
+                None => "input is not valid UTF-8".to_string(),
+            },
+        },
+        _ => Failure::io("read", options.input.as_deref(), e),
+    })?;
+
+    // Leave the line ending a text file normally finishes with alone, so
+    // that plain letters on a line can be encrypted under NonAlpha::Reject.
+    let body = text
+        .strip_suffix('\n')
+        .map(|body| body.strip_suffix('\r').unwrap_or(body))
+        .unwrap_or(&text);
+    let line_ending = &text[body.len()..];
+
+    let result = match options.command {
+        Command::Encrypt => cipher.try_encrypt(body),
+        Command::Decrypt => cipher.try_decrypt(body),
+    };
+    let mut out = result.map_err(|e: CipherError| Failure {
+        status: EXIT_INPUT,
+        message: e.to_string(),
+    })?;
+    out.push_str(line_ending);
+
+    match &options.output {
+        Some(path) => fs::write(path, out),
+        None => io::stdout().write_all(out.as_bytes()),
+    }
+    .map_err(|e| Failure::io("write", options.output.as_deref(), e))
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match parse_args(&args) {
+        Ok(Some(options)) => run(options),
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(failure) => {
+            eprintln!("vigenere: {}", failure.message);
+            eprint!("{}", USAGE);
+            return ExitCode::from(failure.status);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("vigenere: {}", failure.message);
+            ExitCode::from(failure.status)
+        }
+    }
+}
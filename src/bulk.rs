@@ -0,0 +1,213 @@
+//! Enciphering ASCII text in place, a byte at a time.
+//!
+//! When the alphabet is a run of consecutive ASCII characters, such as
+//! `A`–`Z`, and characters outside it are rejected, every byte goes through
+//! the same few arithmetic operations with no lookups or branches.  The key
+//! is expanded into a table of per-byte shifts covering a whole number of
+//! copies of the key, and the data is processed in chunks the size of the
+//! table, so the inner loop has a fixed trip count the compiler can unroll and
+//! vectorize.  Anything else goes through a byte-at-a-time version of
+//! `cipher::transform()`.
+
+use crate::alphabet::Alphabet;
+use crate::cipher::{Keystream, NonAlpha, Tableau};
+use crate::error::CipherError;
+use crate::modular::{add_mod, sub_mod};
+
+// The shift table is at least this many bytes long.
+const TABLE_LEN: usize = 64;
+
+// Returns the first character of the alphabet as a byte if the alphabet is a
+// run of consecutive ASCII characters.
+pub(crate) fn ascii_run(alphabet: &Alphabet) -> Option<u8> {
+    let chars = alphabet.chars();
+    let first = chars[0];
+    let consecutive = chars
+        .iter()
+        .zip(first as u32..)
+        .all(|(&c, expected)| c as u32 == expected);
+
+    (consecutive && chars[chars.len() - 1].is_ascii()).then_some(first as u8)
+}
+
+// Reports the first byte of `data` that isn't a letter, for NonAlpha::Reject.
+// Everything before it is an ASCII letter, so its index is also its position
+// in characters.
+fn reject(data: &[u8], is_letter: impl Fn(u8) -> bool) -> Result<(), CipherError> {
+    let pos = match data.iter().position(|&b| !is_letter(b)) {
+        Some(pos) => pos,
+        None => return Ok(()),
+    };
+
+    let end = data.len().min(pos + 4);
+    let ch = String::from_utf8_lossy(&data[pos..end])
+        .chars()
+        .next()
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    Err(CipherError::InvalidInputChar { pos, ch })
+}
+
+// Enciphers `data` over the run of `n` characters from `first`, starting
+// `key_pos` characters into the key, and returns the key position after it.
+// Nothing is changed if `data` holds a byte outside the run.
+pub(crate) fn rotate_run(
+    data: &mut [u8],
+    first: u8,
+    n: u32,
+    shifts: &[u32],
+    key_pos: usize,
+    tableau: Tableau,
+    decrypt: bool,
+) -> Result<usize, CipherError> {
+    // An ASCII run has at most 128 characters, so everything below fits in a
+    // byte: a letter plus a shift is at most 2 * 127.
+    let size = n as u8;
+
+    // Checking a chunk at a time keeps the common case, where everything is
+    // a letter, free of early exits.
+    let outside = data.chunks(TABLE_LEN).any(|chunk| {
+        chunk.iter().fold(false, |outside, &b| {
+            outside | (b.wrapping_sub(first) >= size)
+        })
+    });
+    if outside {
+        reject(data, |b| b.wrapping_sub(first) < size)?;
+    }
+
+    // Every tableau is a letter, mirrored for Beaufort, plus a shift:
+    // K - P = (n - 1 - P) + (K + 1).
+    let mirror = tableau == Tableau::Beaufort;
+    let shift = |s: u32| -> u8 {
+        let amt = match (tableau, decrypt) {
+            (Tableau::Vigenere, false) | (Tableau::VariantBeaufort, true) => add_mod(s, 0, n),
+            (Tableau::Vigenere, true) | (Tableau::VariantBeaufort, false) => sub_mod(0, s, n),
+            (Tableau::Beaufort, _) => add_mod(s, 1, n),
+        };
+        amt as u8
+    };
+    let table = (0..shifts.len() * TABLE_LEN.div_ceil(shifts.len()))
+        .map(|j| shift(shifts[(key_pos + j) % shifts.len()]))
+        .collect::<Vec<u8>>();
+
+    for chunk in data.chunks_mut(table.len()) {
+        for (b, &amt) in chunk.iter_mut().zip(&table) {
+            let i = b.wrapping_sub(first);
+            let i = if mirror { size - 1 - i } else { i };
+            // add_mod(i, amt, n) without the division: both are below n.
+            let rotated = i + amt;
+            let rotated = if rotated >= size {
+                rotated - size
+            } else {
+                rotated
+            };
+            *b = first + rotated;
+        }
+    }
+
+    Ok((key_pos + data.len()) % shifts.len())
+}
+
+// cipher::transform() for ASCII alphabets, working on bytes in place.  The
+// bytes of a non-ASCII character are never letters, and count as a single
+// character under NonAlpha::PreserveAndAdvance.  Nothing is changed if a
+// character is rejected.
+pub(crate) fn transform_ascii(
+    alphabet: &Alphabet,
+    non_alpha: NonAlpha,
+    data: &mut [u8],
+    keystream: &mut impl Keystream,
+) -> Result<(), CipherError> {
+    if non_alpha == NonAlpha::Reject {
+        reject(data, |b| alphabet.contains(b as char))?;
+    }
+
+    for b in data.iter_mut() {
+        let letter = match non_alpha {
+            _ if !b.is_ascii() => None,
+            NonAlpha::Reject => alphabet.index_of(*b as char).map(|i| (i, false)),
+            _ => alphabet.fold_index(*b as char),
+        };
+
+        if let Some((i, lower)) = letter {
+            *b = alphabet.char_at_case(keystream.letter(i), lower) as u8;
+        } else if non_alpha == NonAlpha::PreserveAndAdvance && *b & 0xc0 != 0x80 {
+            // Continuation bytes are part of the character already counted.
+            keystream.advance();
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_run() {
+        assert_eq!(Some(b'A'), ascii_run(&Alphabet::latin()));
+        assert_eq!(Some(b' '), ascii_run(&Alphabet::printable_ascii()));
+        assert_eq!(None, ascii_run(&Alphabet::alphanumeric()));
+        assert_eq!(None, ascii_run(&Alphabet::greek()));
+        assert_eq!(None, ascii_run(&Alphabet::new("xyz{|}~\u{7f}\u{80}")));
+    }
+
+    #[test]
+    fn test_rotate_run_crosses_chunks() {
+        // Long enough for several chunks, with a key that doesn't divide the
+        // table length.
+        let mut data = b"ATTACKATDAWN".repeat(20);
+        let key_pos = rotate_run(
+            &mut data,
+            b'A',
+            26,
+            &[11, 4, 12, 14, 13],
+            3,
+            Tableau::Vigenere,
+            false,
+        )
+        .unwrap();
+        assert_eq!(3, key_pos);
+
+        // LEMON from its fourth letter on.
+        let expected = crate::VigenereCipher::new("ONLEM").encrypt(&"ATTACKATDAWN".repeat(20));
+        assert_eq!(expected.as_bytes(), &data[..]);
+    }
+
+    #[test]
+    fn test_rotate_run_matches_modular() {
+        // Every letter of every run, under every shift and tableau, against
+        // the rotations the other ciphers use.
+        for n in 1..=128u32 {
+            let first = 128 - n as u8;
+            let letters = (0..n).map(|i| first + i as u8).collect::<Vec<u8>>();
+            for s in (0..n).chain([n, 2 * n - 1, u32::MAX - 1, u32::MAX]) {
+                for tableau in [
+                    Tableau::Vigenere,
+                    Tableau::Beaufort,
+                    Tableau::VariantBeaufort,
+                ] {
+                    for decrypt in [false, true] {
+                        let mut data = letters.clone();
+                        rotate_run(&mut data, first, n, &[s], 0, tableau, decrypt).unwrap();
+                        let rotate = tableau.rotation(decrypt);
+                        let expected = (0..n)
+                            .map(|i| first + rotate(i, s, n) as u8)
+                            .collect::<Vec<u8>>();
+                        assert_eq!(expected, data, "n = {}, s = {}", n, s);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_run_rejects() {
+        let mut data = b"ABC\xce\xbbD".to_vec();
+        assert_eq!(
+            Err(CipherError::InvalidInputChar { pos: 3, ch: 'λ' }),
+            rotate_run(&mut data, b'A', 26, &[1], 0, Tableau::Vigenere, false)
+        );
+        assert_eq!(b"ABC\xce\xbbD", &data[..]);
+    }
+}
@@ -0,0 +1,151 @@
+//! The Vigenère cipher over bytes instead of the characters of an alphabet,
+//! for enciphering binary data.
+
+use crate::error::CipherError;
+
+/// How a data byte is combined with a key byte.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ByteOp {
+    /// The key byte is added modulo 256, the Vigenère cipher over an alphabet
+    /// of every byte value.
+    #[default]
+    Add,
+    /// The key byte is XORed in.  Encryption and decryption are the same
+    /// operation.
+    Xor,
+}
+
+/// A Vigenère cipher over bytes, bound to a key of bytes that is cycled over
+/// the data.
+///
+/// ```
+/// use vigenere_cipher::{ByteCipher, ByteOp};
+///
+/// let cipher = ByteCipher::new(&[1, 2, 255]);
+/// assert_eq!(vec![1, 3, 1, 4], cipher.encrypt(&[0, 1, 2, 3]));
+/// assert_eq!(vec![0, 1, 2, 3], cipher.decrypt(&[1, 3, 1, 4]));
+///
+/// let cipher = ByteCipher::new(b"ICE").with_op(ByteOp::Xor);
+/// assert_eq!(b"\x0b\x36\x37".to_vec(), cipher.encrypt(b"Bur"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteCipher {
+    key: Vec<u8>,
+    op: ByteOp,
+}
+
+impl ByteCipher {
+    /// Creates a cipher that uses `key` for all data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty.  Use [`try_new`](Self::try_new) to handle
+    /// that instead.
+    pub fn new(key: &[u8]) -> ByteCipher {
+        ByteCipher::try_new(key).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that uses `key` for all data, or reports that `key`
+    /// is empty.
+    pub fn try_new(key: &[u8]) -> Result<ByteCipher, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+
+        Ok(ByteCipher {
+            key: key.to_vec(),
+            op: ByteOp::default(),
+        })
+    }
+
+    /// Sets how data bytes are combined with the key.
+    pub fn with_op(mut self, op: ByteOp) -> ByteCipher {
+        self.op = op;
+        self
+    }
+
+    /// Returns how data bytes are combined with the key.
+    pub fn op(&self) -> ByteOp {
+        self.op
+    }
+
+    /// Returns the key this cipher was created with.
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Encrypts `plain_text`, returning the cipher text.
+    pub fn encrypt(&self, plain_text: &[u8]) -> Vec<u8> {
+        let mut cipher_text = plain_text.to_vec();
+        self.encrypt_in_place(&mut cipher_text);
+        cipher_text
+    }
+
+    /// Decrypts `cipher_text`, returning the plain text.
+    pub fn decrypt(&self, cipher_text: &[u8]) -> Vec<u8> {
+        let mut plain_text = cipher_text.to_vec();
+        self.decrypt_in_place(&mut plain_text);
+        plain_text
+    }
+
+    /// Encrypts `data` in place.
+    pub fn encrypt_in_place(&self, data: &mut [u8]) {
+        match self.op {
+            ByteOp::Add => self.combine(data, u8::wrapping_add),
+            ByteOp::Xor => self.combine(data, |b, k| b ^ k),
+        }
+    }
+
+    /// Decrypts `data` in place.
+    pub fn decrypt_in_place(&self, data: &mut [u8]) {
+        match self.op {
+            ByteOp::Add => self.combine(data, u8::wrapping_sub),
+            ByteOp::Xor => self.combine(data, |b, k| b ^ k),
+        }
+    }
+
+    // Combines every byte with the key byte in the same position, cycling
+    // over the key.
+    fn combine(&self, data: &mut [u8], op: impl Fn(u8, u8) -> u8) {
+        for (b, &k) in data.iter_mut().zip(self.key.iter().cycle()) {
+            *b = op(*b, k);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_wraps() {
+        let cipher = ByteCipher::new(&[200]);
+        assert_eq!(vec![44, 199], cipher.encrypt(&[100, 255]));
+        assert_eq!(vec![100, 255], cipher.decrypt(&[44, 199]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        for op in [ByteOp::Add, ByteOp::Xor] {
+            let cipher = ByteCipher::new(b"\x00\x7f\xffkey").with_op(op);
+            let cipher_text = cipher.encrypt(&data);
+            assert_ne!(data, cipher_text);
+            assert_eq!(data, cipher.decrypt(&cipher_text));
+        }
+    }
+
+    #[test]
+    fn test_xor_is_reciprocal() {
+        let cipher = ByteCipher::new(b"ICE").with_op(ByteOp::Xor);
+        let mut data = b"Burning 'em".to_vec();
+        cipher.encrypt_in_place(&mut data);
+        cipher.encrypt_in_place(&mut data);
+        assert_eq!(b"Burning 'em".to_vec(), data);
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        assert_eq!(Err(CipherError::EmptyKey), ByteCipher::try_new(&[]));
+    }
+}
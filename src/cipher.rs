@@ -0,0 +1,681 @@
+//! The [`VigenereCipher`] type and the character rotation it is built on.
+
+use crate::alphabet::Alphabet;
+use crate::bulk::{ascii_run, rotate_run, transform_ascii};
+use crate::error::CipherError;
+use crate::key::Key;
+use crate::modular::{add_mod, sub_mod};
+
+// takes a numeric value that represents a plain text letter and an amount to
+// rotate within an alphabet of `n` characters.  If index = 25 which is Z in
+// A-Z and amt = 1, than 0 which is A should be returned (wraps)
+pub(crate) fn rotate_index(i: u32, amt: u32, n: u32) -> u32 {
+    add_mod(i, amt, n)
+}
+
+// Used by decrypt to undo rotate_index()
+pub(crate) fn reverse_rotate_index(i: u32, amt: u32, n: u32) -> u32 {
+    sub_mod(i, amt, n)
+}
+
+// Beaufort's tableau: the letter is subtracted from the key, which makes it
+// its own inverse.
+fn beaufort_index(i: u32, amt: u32, n: u32) -> u32 {
+    reverse_rotate_index(amt, i, n)
+}
+
+/// The tableau that combines a text letter with a key letter.
+///
+/// In terms of alphabet positions, with P the plain text, C the cipher text
+/// and K the key letter, each reduced modulo the alphabet size:
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tableau {
+    /// C = P + K, the classic Vigenère square.
+    #[default]
+    Vigenere,
+    /// C = K − P.  Encryption and decryption are the same operation.
+    Beaufort,
+    /// C = P − K, which is Vigenère decryption used for encryption.
+    VariantBeaufort,
+}
+
+impl Tableau {
+    // The rotation that encrypts or decrypts a letter with this tableau.
+    pub(crate) fn rotation(self, decrypt: bool) -> fn(u32, u32, u32) -> u32 {
+        match (self, decrypt) {
+            (Tableau::Vigenere, false) | (Tableau::VariantBeaufort, true) => rotate_index,
+            (Tableau::Vigenere, true) | (Tableau::VariantBeaufort, false) => reverse_rotate_index,
+            (Tableau::Beaufort, _) => beaufort_index,
+        }
+    }
+}
+
+/// What to do with characters in the text that aren't in the alphabet.
+///
+/// Under the passthrough modes, the lowercase forms of the letters are also
+/// accepted: they are rotated like their uppercase counterparts and come back
+/// out lowercase.  That is only done when every letter of the alphabet has a
+/// lowercase form that uppercases back to it, so that decrypting always gives
+/// back the original text.  In an alphabet such as
+/// [`Alphabet::alphanumeric`], whose digits have no lowercase form, and for
+/// characters such as `ς`, which uppercases to `Σ` but isn't its lowercase
+/// form, lowercase is passed through like any other character.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonAlpha {
+    /// Only characters in the alphabet are accepted; anything else is an
+    /// error.
+    #[default]
+    Reject,
+    /// Copy other characters to the output unchanged without using up a key
+    /// character, so only the letters are enciphered by the key.
+    Preserve,
+    /// Copy other characters to the output unchanged, but still move on to the
+    /// next key character as if a letter had been enciphered.
+    PreserveAndAdvance,
+}
+
+// Enciphers the letters of a text one at a time.  The state behind it, such as
+// the position in the key, is what makes a cipher polyalphabetic.
+pub(crate) trait Keystream {
+    // Enciphers (or deciphers) the letter at position `i` of the alphabet,
+    // returning the position of the output letter.
+    fn letter(&mut self, i: u32) -> u32;
+
+    // Moves past a character copied under NonAlpha::PreserveAndAdvance.
+    fn advance(&mut self);
+}
+
+// Shared loop behind every cipher: letters go through `keystream` and
+// everything else is handled according to `non_alpha`.
+pub(crate) fn transform(
+    alphabet: &Alphabet,
+    non_alpha: NonAlpha,
+    val: &str,
+    keystream: &mut impl Keystream,
+) -> Result<String, CipherError> {
+    // The output is usually the same length as the input, so allocate
+    // it up front.
+    let mut return_val = String::with_capacity(val.len());
+
+    for (pos, c) in val.chars().enumerate() {
+        // Lowercase letters are only let through by the passthrough
+        // modes, which keep the case of the original text.
+        let letter = match non_alpha {
+            NonAlpha::Reject => alphabet.index_of(c).map(|i| (i, false)),
+            _ => alphabet.fold_index(c),
+        };
+
+        if let Some((i, lower)) = letter {
+            let index = keystream.letter(i);
+            return_val.push(alphabet.char_at_case(index, lower));
+            continue;
+        }
+
+        match non_alpha {
+            NonAlpha::Reject => return Err(CipherError::InvalidInputChar { pos, ch: c }),
+            NonAlpha::Preserve => return_val.push(c),
+            NonAlpha::PreserveAndAdvance => {
+                return_val.push(c);
+                keystream.advance();
+            }
+        }
+    }
+
+    Ok(return_val)
+}
+
+// Counts how many key characters transform() will use up on `val`.
+pub(crate) fn key_positions_needed(alphabet: &Alphabet, non_alpha: NonAlpha, val: &str) -> usize {
+    val.chars()
+        .filter(|&c| match non_alpha {
+            NonAlpha::Reject => alphabet.contains(c),
+            NonAlpha::Preserve => alphabet.fold_index(c).is_some(),
+            NonAlpha::PreserveAndAdvance => true,
+        })
+        .count()
+}
+
+// The Vigenère keystream: the key repeated for as long as the text.
+pub(crate) struct RepeatingKey<'a> {
+    pub(crate) shifts: &'a [u32],
+    // Position in the key, which only moves forward when a key
+    // character is used up.
+    pub(crate) pos: usize,
+    pub(crate) n: u32,
+    pub(crate) rotate: fn(u32, u32, u32) -> u32,
+}
+
+impl Keystream for RepeatingKey<'_> {
+    fn letter(&mut self, i: u32) -> u32 {
+        // Cycle over the key and mod by the length
+        // if a key for example is half the size of the plain text
+        // then each key value will be used twice.
+        let shift_amt = self.shifts[self.pos % self.shifts.len()] % self.n;
+        self.pos += 1;
+
+        (self.rotate)(i, shift_amt, self.n)
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+    }
+}
+
+/// A Vigenère cipher bound to a key.
+///
+/// Every character of the key must be in the cipher's [`Alphabet`], which is
+/// `A`–`Z` unless the cipher was created with [`new_in`](Self::new_in).  By
+/// default so must the text passed to [`encrypt`](Self::encrypt) and
+/// [`decrypt`](Self::decrypt); see [`with_non_alpha`](Self::with_non_alpha)
+/// for handling ordinary prose.  The Beaufort ciphers are the same cipher with
+/// a different [`Tableau`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VigenereCipher {
+    key: Key,
+    alphabet: Alphabet,
+    non_alpha: NonAlpha,
+    tableau: Tableau,
+}
+
+impl VigenereCipher {
+    /// Creates a cipher that uses `key` for every message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty or contains anything other than `A`–`Z`.
+    /// Use [`try_new`](Self::try_new) to handle an invalid key instead.
+    pub fn new(key: &str) -> VigenereCipher {
+        VigenereCipher::try_new(key).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that uses `key` for every message, or reports why
+    /// `key` can't be used.
+    pub fn try_new(key: &str) -> Result<VigenereCipher, CipherError> {
+        VigenereCipher::try_new_in(key, Alphabet::default())
+    }
+
+    /// Creates a cipher that rotates characters within `alphabet` instead of
+    /// `A`–`Z`.
+    ///
+    /// ```
+    /// use vigenere_cipher::{Alphabet, VigenereCipher};
+    ///
+    /// let cipher = VigenereCipher::new_in("КЛЮЧ", Alphabet::cyrillic());
+    /// let cipher_text = cipher.encrypt("ПРИВЕТ");
+    /// assert_eq!("ПРИВЕТ", cipher.decrypt(&cipher_text));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty or contains a character outside `alphabet`.
+    /// Use [`try_new_in`](Self::try_new_in) to handle an invalid key instead.
+    pub fn new_in(key: &str, alphabet: Alphabet) -> VigenereCipher {
+        VigenereCipher::try_new_in(key, alphabet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that rotates characters within `alphabet`, or
+    /// reports why `key` can't be used with it.
+    pub fn try_new_in(key: &str, alphabet: Alphabet) -> Result<VigenereCipher, CipherError> {
+        let key = Key::parse(key, &alphabet)?;
+        Ok(VigenereCipher::from_key(key, alphabet))
+    }
+
+    /// Creates a Gronsfeld cipher: a Vigenère cipher over `A`–`Z` whose key
+    /// is a string of digits, each giving the amount to shift by.
+    ///
+    /// ```
+    /// use vigenere_cipher::VigenereCipher;
+    ///
+    /// let cipher = VigenereCipher::gronsfeld("12345");
+    /// assert_eq!("IGOPT", cipher.encrypt("HELLO"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digits` is empty or contains anything other than `0`–`9`.
+    /// Use [`try_gronsfeld`](Self::try_gronsfeld) to handle an invalid key
+    /// instead.
+    pub fn gronsfeld(digits: &str) -> VigenereCipher {
+        VigenereCipher::try_gronsfeld(digits).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a Gronsfeld cipher over `A`–`Z`, or reports why `digits`
+    /// can't be used as its key.
+    pub fn try_gronsfeld(digits: &str) -> Result<VigenereCipher, CipherError> {
+        let key = Key::from_digits(digits)?;
+        Ok(VigenereCipher::from_key(key, Alphabet::default()))
+    }
+
+    /// Creates a cipher from an already validated key.
+    pub fn from_key(key: Key, alphabet: Alphabet) -> VigenereCipher {
+        VigenereCipher {
+            key,
+            alphabet,
+            non_alpha: NonAlpha::default(),
+            tableau: Tableau::default(),
+        }
+    }
+
+    /// Sets how characters outside the alphabet are handled.
+    ///
+    /// ```
+    /// use vigenere_cipher::{NonAlpha, VigenereCipher};
+    ///
+    /// let cipher = VigenereCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+    /// assert_eq!("Lxfopv ef rnhr!", cipher.encrypt("Attack at dawn!"));
+    /// ```
+    pub fn with_non_alpha(mut self, non_alpha: NonAlpha) -> VigenereCipher {
+        self.non_alpha = non_alpha;
+        self
+    }
+
+    /// Returns how characters outside the alphabet are handled.
+    pub fn non_alpha(&self) -> NonAlpha {
+        self.non_alpha
+    }
+
+    /// Sets the tableau letters are combined with the key by.
+    ///
+    /// ```
+    /// use vigenere_cipher::{Tableau, VigenereCipher};
+    ///
+    /// let cipher = VigenereCipher::new("FORTIFICATION").with_tableau(Tableau::Beaufort);
+    /// assert_eq!("CKMPVCPVWPIW", cipher.encrypt("DEFENDTHEEAS"));
+    /// assert_eq!("DEFENDTHEEAS", cipher.encrypt("CKMPVCPVWPIW"));
+    /// ```
+    pub fn with_tableau(mut self, tableau: Tableau) -> VigenereCipher {
+        self.tableau = tableau;
+        self
+    }
+
+    /// Returns the tableau letters are combined with the key by.
+    pub fn tableau(&self) -> Tableau {
+        self.tableau
+    }
+
+    /// Returns the key this cipher was created with.
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    /// Returns the alphabet this cipher rotates characters within.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Encrypts `plain_text`, returning the cipher text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plain_text` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn encrypt(&self, plain_text: &str) -> String {
+        self.try_encrypt(plain_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Decrypts `cipher_text`, returning the plain text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cipher_text` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn decrypt(&self, cipher_text: &str) -> String {
+        self.try_decrypt(cipher_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts `plain_text`, or reports the first character that can't be
+    /// handled.
+    pub fn try_encrypt(&self, plain_text: &str) -> Result<String, CipherError> {
+        self.transform(plain_text, false, &mut 0)
+    }
+
+    /// Decrypts `cipher_text`, or reports the first character that can't be
+    /// handled.
+    pub fn try_decrypt(&self, cipher_text: &str) -> Result<String, CipherError> {
+        self.transform(cipher_text, true, &mut 0)
+    }
+
+    /// Encrypts `data`, a buffer of ASCII text, in place.
+    ///
+    /// This is the fast path for bulk data.  When the alphabet is a run of
+    /// consecutive ASCII characters, like the default `A`–`Z`, and characters
+    /// outside it are rejected, the whole buffer is enciphered in
+    /// vectorizable chunks.  Otherwise the result is the same as
+    /// [`encrypt`](Self::encrypt) would give; the bytes of any non-ASCII
+    /// characters are left alone or rejected according to
+    /// [`non_alpha`](Self::non_alpha).
+    ///
+    /// ```
+    /// use vigenere_cipher::VigenereCipher;
+    ///
+    /// let mut data = *b"CRYPTO";
+    /// VigenereCipher::new("DUH").encrypt_in_place(&mut data);
+    /// assert_eq!(b"FLFSNV", &data);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alphabet has a character outside ASCII, or if `data`
+    /// contains a character that is rejected under [`NonAlpha::Reject`].
+    pub fn encrypt_in_place(&self, data: &mut [u8]) {
+        self.try_encrypt_in_place(data)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Decrypts `data`, a buffer of ASCII text, in place, as the counterpart
+    /// of [`encrypt_in_place`](Self::encrypt_in_place).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alphabet has a character outside ASCII, or if `data`
+    /// contains a character that is rejected under [`NonAlpha::Reject`].
+    pub fn decrypt_in_place(&self, data: &mut [u8]) {
+        self.try_decrypt_in_place(data)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts `data` in place, or reports why it can't be.  `data` is left
+    /// unchanged on error.
+    pub fn try_encrypt_in_place(&self, data: &mut [u8]) -> Result<(), CipherError> {
+        self.transform_in_place(data, false, &mut 0)
+    }
+
+    /// Decrypts `data` in place, or reports why it can't be.  `data` is left
+    /// unchanged on error.
+    pub fn try_decrypt_in_place(&self, data: &mut [u8]) -> Result<(), CipherError> {
+        self.transform_in_place(data, true, &mut 0)
+    }
+
+    // The byte counterpart of transform().
+    fn transform_in_place(
+        &self,
+        data: &mut [u8],
+        decrypt: bool,
+        key_pos: &mut usize,
+    ) -> Result<(), CipherError> {
+        if !self.alphabet.chars().iter().all(char::is_ascii) {
+            return Err(CipherError::NonAsciiAlphabet);
+        }
+
+        let n = self.alphabet.size() as u32;
+        if let (NonAlpha::Reject, Some(first)) = (self.non_alpha, ascii_run(&self.alphabet)) {
+            let shifts = self.key.shifts();
+            *key_pos = rotate_run(data, first, n, shifts, *key_pos, self.tableau, decrypt)?;
+            return Ok(());
+        }
+
+        let mut keystream = RepeatingKey {
+            shifts: self.key.shifts(),
+            pos: *key_pos,
+            n,
+            rotate: self.tableau.rotation(decrypt),
+        };
+        transform_ascii(&self.alphabet, self.non_alpha, data, &mut keystream)?;
+        *key_pos = keystream.pos % self.key.shifts().len();
+        Ok(())
+    }
+
+    // Runs the tableau's rotation over every letter with the shift taken from
+    // the key, starting `key_pos` characters into the key.  `key_pos` is left
+    // pointing after the last key character used, so a stream can carry on
+    // enciphering where the previous chunk stopped.
+    pub(crate) fn transform(
+        &self,
+        val: &str,
+        decrypt: bool,
+        key_pos: &mut usize,
+    ) -> Result<String, CipherError> {
+        // ASCII text over an ASCII run can take the fast path.
+        if val.is_ascii() && ascii_run(&self.alphabet).is_some() {
+            let mut bytes = val.as_bytes().to_vec();
+            self.transform_in_place(&mut bytes, decrypt, key_pos)?;
+            return Ok(String::from_utf8(bytes).expect("ASCII letters encipher to ASCII"));
+        }
+
+        let mut keystream = RepeatingKey {
+            shifts: self.key.shifts(),
+            pos: *key_pos,
+            n: self.alphabet.size() as u32,
+            rotate: self.tableau.rotation(decrypt),
+        };
+        let result = transform(&self.alphabet, self.non_alpha, val, &mut keystream);
+        *key_pos = keystream.pos % self.key.shifts().len();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_index() {
+        assert_eq!(0, rotate_index(25, 1, 26));
+        assert_eq!(25, reverse_rotate_index(0, 1, 26));
+    }
+
+    #[test]
+    fn test_enc() {
+        let cipher = VigenereCipher::new("DUH");
+        assert_eq!("FLFSNV", cipher.encrypt("CRYPTO"));
+        assert_eq!("WBLBXYLHRWBLWYH", cipher.encrypt("THEYDRINKTHETEA"));
+    }
+
+    #[test]
+    fn test_dec() {
+        let cipher = VigenereCipher::new("DUH");
+        assert_eq!("CRYPTO", cipher.decrypt("FLFSNV"));
+        assert_eq!("THEYDRINKTHETEA", cipher.decrypt("WBLBXYLHRWBLWYH"));
+    }
+
+    #[test]
+    fn test_cipher_round_trip() {
+        let cipher = VigenereCipher::new("DUH");
+        assert_eq!("DUH", cipher.key());
+        assert_eq!("WBLBXYLHRWBLWYH", cipher.encrypt("THEYDRINKTHETEA"));
+        assert_eq!("THEYDRINKTHETEA", cipher.decrypt("WBLBXYLHRWBLWYH"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cipher_rejects_empty_key() {
+        VigenereCipher::new("");
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        assert_eq!(Err(CipherError::EmptyKey), VigenereCipher::try_new(""));
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 1, ch: 'u' }),
+            VigenereCipher::try_new("DuH")
+        );
+    }
+
+    #[test]
+    fn test_try_encrypt_rejects_invalid_input() {
+        let cipher = VigenereCipher::new("DUH");
+        assert_eq!(
+            Err(CipherError::InvalidInputChar { pos: 6, ch: ' ' }),
+            cipher.try_encrypt("ATTACK AT DAWN")
+        );
+        assert_eq!(
+            Err(CipherError::InvalidInputChar { pos: 0, ch: 'é' }),
+            cipher.try_decrypt("éA")
+        );
+        assert_eq!(Ok("FLFSNV".to_string()), cipher.try_encrypt("CRYPTO"));
+        assert_eq!(Ok(String::new()), cipher.try_decrypt(""));
+    }
+
+    #[test]
+    fn test_preserve_non_alpha() {
+        let cipher = VigenereCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+        assert_eq!("Lxfopv ef rnhr!", cipher.encrypt("Attack at dawn!"));
+        assert_eq!("Attack at dawn!", cipher.decrypt("Lxfopv ef rnhr!"));
+
+        // Only letters use up the key, so the letters encrypt the same way
+        // as the bare uppercase message.
+        let strict = VigenereCipher::new("LEMON");
+        assert_eq!("LXFOPVEFRNHR", strict.encrypt("ATTACKATDAWN"));
+    }
+
+    #[test]
+    fn test_preserve_and_advance_non_alpha() {
+        let cipher = VigenereCipher::new("LEMON").with_non_alpha(NonAlpha::PreserveAndAdvance);
+        let cipher_text = cipher.encrypt("Attack at dawn!");
+        assert_eq!("Lxfopv mh oeib!", cipher_text);
+        assert_eq!("Attack at dawn!", cipher.decrypt(&cipher_text));
+    }
+
+    #[test]
+    fn test_reject_does_not_fold_case() {
+        let cipher = VigenereCipher::new("LEMON");
+        assert_eq!(
+            Err(CipherError::InvalidInputChar { pos: 1, ch: 't' }),
+            cipher.try_encrypt("Attack")
+        );
+    }
+
+    #[test]
+    fn test_other_alphabets() {
+        let cipher = VigenereCipher::new_in("KEY9", Alphabet::alphanumeric());
+        assert_eq!("KEY9", cipher.encrypt("AAAA"));
+        assert_eq!("ATTACK0900", cipher.decrypt(&cipher.encrypt("ATTACK0900")));
+
+        let cipher =
+            VigenereCipher::new_in("ΚΛΕΙΔΙ", Alphabet::greek()).with_non_alpha(NonAlpha::Preserve);
+        let plain_text = "Καλημέρα κόσμε";
+        let cipher_text = cipher.encrypt(plain_text);
+        assert_ne!(plain_text, cipher_text);
+        assert_eq!(plain_text, cipher.decrypt(&cipher_text));
+
+        let cipher = VigenereCipher::new_in("Key 1!", Alphabet::printable_ascii());
+        assert_eq!(
+            "Hello, world",
+            cipher.decrypt(&cipher.encrypt("Hello, world"))
+        );
+    }
+
+    #[test]
+    fn test_passthrough_round_trips_every_character() {
+        // ς uppercases to Σ, which lowercases to σ, so it is left alone.
+        let greek = VigenereCipher::new_in("ΚΛΕΙΔΙ", Alphabet::greek());
+        // The digits have no lowercase form, so lowercase letters are too.
+        let alphanumeric = VigenereCipher::new_in("KEY9", Alphabet::alphanumeric());
+
+        for non_alpha in [NonAlpha::Preserve, NonAlpha::PreserveAndAdvance] {
+            let greek = greek.clone().with_non_alpha(non_alpha);
+            assert_eq!("λόγος", greek.decrypt(&greek.encrypt("λόγος")));
+            assert!(greek.encrypt("λόγος").ends_with('ς'));
+
+            let alphanumeric = alphanumeric.clone().with_non_alpha(non_alpha);
+            assert_eq!("th", alphanumeric.decrypt(&alphanumeric.encrypt("th")));
+            assert_eq!(
+                "the END",
+                alphanumeric.decrypt(&alphanumeric.encrypt("the END"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_must_be_in_alphabet() {
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 1, ch: 'E' }),
+            VigenereCipher::try_new_in("КEY", Alphabet::cyrillic())
+        );
+    }
+
+    #[test]
+    fn test_beaufort() {
+        // From Practical Cryptography's Beaufort page.
+        let cipher = VigenereCipher::new("FORTIFICATION").with_tableau(Tableau::Beaufort);
+        let plain_text = "DEFENDTHEEASTWALLOFTHECASTLE";
+        let cipher_text = "CKMPVCPVWPIWUJOGIUAPVWRIWUUK";
+        assert_eq!(cipher_text, cipher.encrypt(plain_text));
+        assert_eq!(plain_text, cipher.decrypt(cipher_text));
+        assert_eq!(plain_text, cipher.encrypt(cipher_text));
+    }
+
+    #[test]
+    fn test_variant_beaufort() {
+        let vigenere = VigenereCipher::new("LEMON");
+        let variant = VigenereCipher::new("LEMON").with_tableau(Tableau::VariantBeaufort);
+        let cipher_text = variant.encrypt("ATTACKATDAWN");
+        assert_eq!(vigenere.decrypt("ATTACKATDAWN"), cipher_text);
+        assert_eq!("ATTACKATDAWN", variant.decrypt(&cipher_text));
+    }
+
+    #[test]
+    fn test_tableaus_with_other_options() {
+        for tableau in [
+            Tableau::Vigenere,
+            Tableau::Beaufort,
+            Tableau::VariantBeaufort,
+        ] {
+            let cipher = VigenereCipher::new_in("КЛЮЧ", Alphabet::cyrillic())
+                .with_tableau(tableau)
+                .with_non_alpha(NonAlpha::PreserveAndAdvance);
+            let cipher_text = cipher.encrypt("Привет, мир!");
+            assert_eq!("Привет, мир!", cipher.decrypt(&cipher_text));
+        }
+    }
+
+    #[test]
+    fn test_gronsfeld() {
+        let cipher = VigenereCipher::gronsfeld("31415");
+        assert_eq!("31415", cipher.key());
+        let cipher_text = cipher.encrypt("ATTACKATDAWN");
+        assert_eq!(
+            VigenereCipher::new("DBEBF").encrypt("ATTACKATDAWN"),
+            cipher_text
+        );
+        assert_eq!("ATTACKATDAWN", cipher.decrypt(&cipher_text));
+
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 2, ch: 'A' }),
+            VigenereCipher::try_gronsfeld("12A")
+        );
+    }
+
+    #[test]
+    fn test_in_place_matches_encrypt() {
+        // The Ç keeps encrypt() on the character loop.
+        let text = "Ça, c'est la vie! Attack at dawn.";
+        for non_alpha in [NonAlpha::Preserve, NonAlpha::PreserveAndAdvance] {
+            for tableau in [
+                Tableau::Vigenere,
+                Tableau::Beaufort,
+                Tableau::VariantBeaufort,
+            ] {
+                let cipher = VigenereCipher::new("LEMON")
+                    .with_non_alpha(non_alpha)
+                    .with_tableau(tableau);
+                let mut data = text.as_bytes().to_vec();
+                cipher.encrypt_in_place(&mut data);
+                assert_eq!(cipher.encrypt(text).as_bytes(), &data[..]);
+                cipher.decrypt_in_place(&mut data);
+                assert_eq!(text.as_bytes(), &data[..]);
+            }
+        }
+
+        let cipher = VigenereCipher::new_in("KEY42", Alphabet::alphanumeric());
+        let mut data = b"ROUTE66".to_vec();
+        cipher.encrypt_in_place(&mut data);
+        assert_eq!(cipher.encrypt("ROUTE66").as_bytes(), &data[..]);
+    }
+
+    #[test]
+    fn test_in_place_errors() {
+        let mut data = b"CRYPTO 101".to_vec();
+        assert_eq!(
+            Err(CipherError::InvalidInputChar { pos: 6, ch: ' ' }),
+            VigenereCipher::new("DUH").try_encrypt_in_place(&mut data)
+        );
+        assert_eq!(b"CRYPTO 101", &data[..]);
+        assert_eq!(
+            Err(CipherError::NonAsciiAlphabet),
+            VigenereCipher::new_in("КЛЮЧ", Alphabet::cyrillic()).try_encrypt_in_place(&mut data)
+        );
+    }
+}
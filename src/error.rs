@@ -0,0 +1,104 @@
+//! The error type returned by the fallible cipher operations.
+
+use std::error::Error;
+use std::fmt;
+
+/// Why a key or a piece of text was rejected.
+///
+/// Positions count `char`s from the start of the key or text, not bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CipherError {
+    /// The key has no characters, so there is nothing to rotate by.
+    EmptyKey,
+    /// The key contains a character outside the alphabet.
+    InvalidKeyChar { pos: usize, ch: char },
+    /// The text being encrypted or decrypted contains a character outside
+    /// the alphabet.
+    InvalidInputChar { pos: usize, ch: char },
+    /// A running key ran out before the end of the text: `needed` key
+    /// characters were needed, but only `available` were left.
+    KeyTooShort { needed: usize, available: usize },
+    /// A Quagmire cipher's indicator is not in the alphabet.
+    InvalidIndicator { ch: char },
+    /// A keyword an alphabet is mixed by, such as a Quagmire cipher's,
+    /// contains a character outside the alphabet.
+    InvalidKeywordChar { pos: usize, ch: char },
+    /// A Porta cipher was given an alphabet that can't be split into two
+    /// halves.
+    OddAlphabetSize { size: usize },
+    /// The key contains a grapheme cluster outside a `GraphemeAlphabet`.
+    InvalidKeyGrapheme { pos: usize, grapheme: String },
+    /// The text being encrypted or decrypted contains a grapheme cluster
+    /// outside a `GraphemeAlphabet`.
+    InvalidInputGrapheme { pos: usize, grapheme: String },
+    /// Text was to be enciphered as bytes in place with an alphabet that has
+    /// characters outside ASCII.
+    NonAsciiAlphabet,
+    /// An alphabet was built from no characters.
+    EmptyAlphabet,
+    /// An alphabet was built from characters that repeat.
+    DuplicateAlphabetChar { pos: usize, ch: char },
+    /// A grapheme alphabet was built from grapheme clusters that repeat.
+    DuplicateAlphabetGrapheme { pos: usize, grapheme: String },
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::EmptyKey => write!(f, "key must not be empty"),
+            CipherError::InvalidKeyChar { pos, ch } => {
+                write!(f, "invalid character {:?} in key at position {}", ch, pos)
+            }
+            CipherError::InvalidInputChar { pos, ch } => {
+                write!(f, "invalid character {:?} in input at position {}", ch, pos)
+            }
+            CipherError::KeyTooShort { needed, available } => write!(
+                f,
+                "key is too short: {} characters needed but only {} available",
+                needed, available
+            ),
+            CipherError::InvalidIndicator { ch } => {
+                write!(f, "indicator {:?} is not in the alphabet", ch)
+            }
+            CipherError::InvalidKeywordChar { pos, ch } => write!(
+                f,
+                "invalid character {:?} in alphabet keyword at position {}",
+                ch, pos
+            ),
+            CipherError::OddAlphabetSize { size } => write!(
+                f,
+                "alphabet must have an even number of characters, not {}",
+                size
+            ),
+            CipherError::InvalidKeyGrapheme { pos, grapheme } => write!(
+                f,
+                "invalid grapheme {:?} in key at position {}",
+                grapheme, pos
+            ),
+            CipherError::InvalidInputGrapheme { pos, grapheme } => write!(
+                f,
+                "invalid grapheme {:?} in input at position {}",
+                grapheme, pos
+            ),
+            CipherError::NonAsciiAlphabet => {
+                write!(f, "alphabet must be ASCII to encipher bytes in place")
+            }
+            CipherError::EmptyAlphabet => write!(f, "alphabet must not be empty"),
+            CipherError::DuplicateAlphabetChar { pos, ch } => {
+                write!(
+                    f,
+                    "duplicate character {:?} in alphabet at position {}",
+                    ch, pos
+                )
+            }
+            CipherError::DuplicateAlphabetGrapheme { pos, grapheme } => write!(
+                f,
+                "duplicate grapheme {:?} in alphabet at position {}",
+                grapheme, pos
+            ),
+        }
+    }
+}
+
+impl Error for CipherError {}
@@ -0,0 +1,618 @@
+//! The [`Key`] every cipher takes its shifts from, and the [`KeyBuilder`]
+//! that derives one from a passphrase.
+
+#[cfg(feature = "rand")]
+use rand_core::Rng;
+#[cfg(feature = "unicode")]
+use unicode_normalization::char::is_combining_mark;
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+
+use crate::alphabet::Alphabet;
+use crate::error::CipherError;
+
+/// A validated key: the text it was written as, and the amount each of its
+/// characters shifts by.
+///
+/// A key is checked against an alphabet when it is parsed, but the shifts are
+/// just numbers; a cipher over a smaller alphabet wraps them around.
+///
+/// ```
+/// use vigenere_cipher::{Alphabet, Key};
+///
+/// let key = Key::parse("DUH", &Alphabet::latin()).unwrap();
+/// assert_eq!("DUH", key.as_str());
+/// assert_eq!(&[3, 20, 7], key.shifts());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Key {
+    text: String,
+    shifts: Vec<u32>,
+}
+
+impl Key {
+    /// Converts every character of `text` to its position in `alphabet`, or
+    /// reports why `text` can't be used as a key.
+    pub fn parse(text: &str, alphabet: &Alphabet) -> Result<Key, CipherError> {
+        if text.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+
+        // Find the amount to shift by for each key char.
+        let shifts = text
+            .chars()
+            .enumerate()
+            .map(|(pos, ch)| {
+                alphabet
+                    .index_of(ch)
+                    .ok_or(CipherError::InvalidKeyChar { pos, ch })
+            })
+            .collect::<Result<Vec<u32>, CipherError>>()?;
+
+        Ok(Key {
+            text: text.to_string(),
+            shifts,
+        })
+    }
+
+    /// Parses a Gronsfeld key: a string of the digits `0`–`9`, each giving
+    /// the amount to shift by directly.
+    ///
+    /// ```
+    /// use vigenere_cipher::{Key, VigenereCipher};
+    ///
+    /// let key = Key::from_digits("31415").unwrap();
+    /// assert_eq!(&[3, 1, 4, 1, 5], key.shifts());
+    /// assert_eq!(
+    ///     VigenereCipher::new("DBEBF").encrypt("ATTACKATDAWN"),
+    ///     VigenereCipher::from_key(key, Default::default()).encrypt("ATTACKATDAWN")
+    /// );
+    /// ```
+    pub fn from_digits(digits: &str) -> Result<Key, CipherError> {
+        if digits.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+
+        let shifts = digits
+            .chars()
+            .enumerate()
+            .map(|(pos, ch)| {
+                ch.to_digit(10)
+                    .ok_or(CipherError::InvalidKeyChar { pos, ch })
+            })
+            .collect::<Result<Vec<u32>, CipherError>>()?;
+
+        Ok(Key {
+            text: digits.to_string(),
+            shifts,
+        })
+    }
+
+    /// Generates a key of `len` letters of `A`–`Z`, each chosen uniformly at
+    /// random by `rng`.
+    ///
+    /// For a key that has to stay secret, `rng` should be a cryptographically
+    /// secure generator, such as `rand::rngs::SysRng`; a seeded one, such as
+    /// `rand::rngs::StdRng`, gives the same key every time, for tests and
+    /// exercises.
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use vigenere_cipher::{Alphabet, Key};
+    ///
+    /// let key = Key::generate(12, &mut StdRng::seed_from_u64(42));
+    /// assert_eq!(12, key.as_str().len());
+    /// assert_eq!(key, Key::generate(12, &mut StdRng::seed_from_u64(42)));
+    /// assert_eq!(12.0 * 26f64.log2(), key.entropy_bits(&Alphabet::latin()));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is 0.
+    #[cfg(feature = "rand")]
+    pub fn generate<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Key {
+        Key::generate_in(len, &Alphabet::latin(), rng)
+    }
+
+    /// Generates a key of `len` letters of `alphabet`, each chosen uniformly
+    /// at random by `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is 0.
+    #[cfg(feature = "rand")]
+    pub fn generate_in<R: Rng + ?Sized>(len: usize, alphabet: &Alphabet, rng: &mut R) -> Key {
+        Key::try_generate_in(len, alphabet, rng).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Generates a key of `len` letters of `A`–`Z`, or reports why it can't
+    /// be.
+    #[cfg(feature = "rand")]
+    pub fn try_generate<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Result<Key, CipherError> {
+        Key::try_generate_in(len, &Alphabet::latin(), rng)
+    }
+
+    /// Generates a key of `len` letters of `alphabet`, or reports why it
+    /// can't be.
+    #[cfg(feature = "rand")]
+    pub fn try_generate_in<R: Rng + ?Sized>(
+        len: usize,
+        alphabet: &Alphabet,
+        rng: &mut R,
+    ) -> Result<Key, CipherError> {
+        if len == 0 {
+            return Err(CipherError::EmptyKey);
+        }
+
+        let n = alphabet.size() as u32;
+        let shifts = (0..len).map(|_| uniform(rng, n)).collect::<Vec<u32>>();
+        Ok(Key {
+            text: shifts.iter().map(|&i| alphabet.char_at(i)).collect(),
+            shifts,
+        })
+    }
+
+    /// Returns how many bits of entropy a key of this length over `alphabet`
+    /// has when every letter is chosen uniformly at random, as
+    /// `Key::generate` does: the base 2 logarithm of the number of such keys.
+    ///
+    /// This is a property of how the key was chosen, not of the letters it
+    /// happened to get, so `AA` has as many bits as `AB`.  For a key chosen
+    /// by a person, such as a word, it is only an upper bound: such a key has
+    /// far less entropy than its length suggests.
+    ///
+    /// ```
+    /// use vigenere_cipher::{Alphabet, Key};
+    ///
+    /// let latin = Alphabet::latin();
+    /// let key = Key::parse("LEMON", &latin).unwrap();
+    /// assert_eq!(5.0 * 26f64.log2(), key.entropy_bits(&latin));
+    /// assert_eq!(5.0, key.entropy_bits(&Alphabet::new("01")));
+    /// ```
+    pub fn entropy_bits(&self, alphabet: &Alphabet) -> f64 {
+        self.shifts.len() as f64 * (alphabet.size() as f64).log2()
+    }
+
+    /// Returns the key as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the amount each character of the key shifts by.
+    pub fn shifts(&self) -> &[u32] {
+        &self.shifts
+    }
+}
+
+/// What a [`KeyBuilder`] does with the digits `0`–`9` when they aren't in
+/// its alphabet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DigitMapping {
+    /// Remove them, like any other character outside the alphabet.
+    #[default]
+    Drop,
+    /// Replace each digit with the letter at that position in the alphabet,
+    /// so `0` becomes `A` and `9` becomes `J` over `A`–`Z`.  The letter
+    /// shifts by the same amount as the digit does in a Gronsfeld key (see
+    /// [`Key::from_digits`]).
+    Letters,
+}
+
+/// A character of a passphrase that didn't make it into the derived key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RemovedChar {
+    /// The position of the character in the passphrase, in `char`s.
+    pub pos: usize,
+    pub ch: char,
+}
+
+/// A character of a passphrase that is in the derived key as other letters,
+/// such as `é` as `E` or `œ` as `OE`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transliteration {
+    /// The position of the character in the passphrase, in `char`s.
+    pub pos: usize,
+    pub ch: char,
+    /// The letters of the key that stand for it.
+    pub replacement: String,
+}
+
+/// The key derived from a passphrase, and what was changed to get it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivedKey {
+    pub key: Key,
+    /// Every character of the passphrase that isn't in the key in any form,
+    /// in the order they appear.
+    pub removed: Vec<RemovedChar>,
+    /// Every character of the passphrase that is in the key as something
+    /// other than itself or its uppercase form, in the order they appear.
+    pub transliterated: Vec<Transliteration>,
+}
+
+/// Derives keys from passphrases typed by people, such as
+/// `my secret phrase 2024`, which [`Key::parse`] would reject.
+///
+/// Each character of the passphrase is turned into letters of the alphabet:
+///
+/// - a letter of the alphabet, in either case, becomes that letter;
+/// - anything else is uppercased and, with the `unicode` feature, decomposed
+///   into its compatibility form with the accents dropped, so `é` becomes
+///   `E`, `ß` becomes `SS` and `ﬁ` becomes `FI`;
+/// - letters that Unicode doesn't decompose are spelled out, so `Œ` becomes
+///   `OE`, `Ø` becomes `O` and `Þ` becomes `TH`;
+/// - a digit is mapped as the [`DigitMapping`] says;
+/// - whatever is left, such as spaces and punctuation, is removed.
+///
+/// Removed characters are reported in [`DerivedKey::removed`], and characters
+/// replaced by anything but their uppercase form in
+/// [`DerivedKey::transliterated`].
+///
+/// ```
+/// use vigenere_cipher::{DigitMapping, KeyBuilder, RemovedChar};
+///
+/// let derived = KeyBuilder::new().derive(" Top secret!").unwrap();
+/// assert_eq!("TOPSECRET", derived.key.as_str());
+/// assert_eq!(
+///     vec![
+///         RemovedChar { pos: 0, ch: ' ' },
+///         RemovedChar { pos: 4, ch: ' ' },
+///         RemovedChar { pos: 11, ch: '!' },
+///     ],
+///     derived.removed
+/// );
+///
+/// let derived = KeyBuilder::new()
+///     .with_digits(DigitMapping::Letters)
+///     .derive("my secret phrase 2024")
+///     .unwrap();
+/// assert_eq!("MYSECRETPHRASECACE", derived.key.as_str());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct KeyBuilder {
+    alphabet: Alphabet,
+    digits: DigitMapping,
+}
+
+impl KeyBuilder {
+    /// Creates a builder for keys over `A`–`Z` that drops digits.
+    pub fn new() -> KeyBuilder {
+        KeyBuilder::new_in(Alphabet::latin())
+    }
+
+    /// Creates a builder for keys over `alphabet` that drops digits.
+    pub fn new_in(alphabet: Alphabet) -> KeyBuilder {
+        KeyBuilder {
+            alphabet,
+            digits: DigitMapping::default(),
+        }
+    }
+
+    /// Sets what happens to digits outside the alphabet.
+    pub fn with_digits(mut self, digits: DigitMapping) -> KeyBuilder {
+        self.digits = digits;
+        self
+    }
+
+    /// Returns the alphabet keys are derived over.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Returns what happens to digits outside the alphabet.
+    pub fn digits(&self) -> DigitMapping {
+        self.digits
+    }
+
+    /// Derives a key from `passphrase`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CipherError::EmptyKey`] if nothing in `passphrase` can be
+    /// turned into a letter of the alphabet.
+    pub fn derive(&self, passphrase: &str) -> Result<DerivedKey, CipherError> {
+        let mut text = String::with_capacity(passphrase.len());
+        let mut removed = Vec::new();
+        let mut transliterated = Vec::new();
+
+        for (pos, ch) in passphrase.chars().enumerate() {
+            let letters = match self.letter(ch) {
+                Some(letter) => Some(letter.to_string()),
+                None => decompose(ch)
+                    .into_iter()
+                    .map(|c| self.letter(c))
+                    .collect::<Option<String>>()
+                    .filter(|letters| !letters.is_empty()),
+            };
+
+            match letters {
+                Some(letters) => {
+                    // ß becoming SS and ﬁ becoming FI are only uppercasing.
+                    let upper = ch.to_uppercase().collect::<String>();
+                    if letters != upper && letters != ch.to_string() {
+                        transliterated.push(Transliteration {
+                            pos,
+                            ch,
+                            replacement: letters.clone(),
+                        });
+                    }
+                    text.push_str(&letters);
+                }
+                None => removed.push(RemovedChar { pos, ch }),
+            }
+        }
+
+        Ok(DerivedKey {
+            key: Key::parse(&text, &self.alphabet)?,
+            removed,
+            transliterated,
+        })
+    }
+
+    // The letter `c` stands for on its own: itself, its uppercase form, or
+    // the letter a digit maps to.
+    fn letter(&self, c: char) -> Option<char> {
+        if self.alphabet.contains(c) {
+            return Some(c);
+        }
+
+        let mut upper = c.to_uppercase();
+        if let (Some(u), None) = (upper.next(), upper.next()) {
+            if self.alphabet.contains(u) {
+                return Some(u);
+            }
+        }
+
+        match (self.digits, c.to_digit(10)) {
+            (DigitMapping::Letters, Some(d)) if (d as usize) < self.alphabet.size() => {
+                Some(self.alphabet.char_at(d))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Shortens a key that is the same shorter key repeated, e.g. LEMONLEMON.
+pub(crate) fn minimal_key<T: PartialEq>(key: &[T]) -> &[T] {
+    (1..key.len())
+        .filter(|&len| key.len().is_multiple_of(len))
+        .map(|len| &key[..len])
+        .find(|short| key.chunks(short.len()).all(|chunk| chunk == *short))
+        .unwrap_or(key)
+}
+
+// A number below `n` chosen uniformly at random.  Taking the remainder of
+// any u32 would favour the small numbers, so draws from the incomplete last
+// run of `n` are thrown away.
+#[cfg(feature = "rand")]
+fn uniform<R: Rng + ?Sized>(rng: &mut R, n: u32) -> u32 {
+    let limit = u32::MAX - u32::MAX % n;
+    loop {
+        let x = rng.next_u32();
+        if x < limit {
+            return x % n;
+        }
+    }
+}
+
+// Uppercase letters that are letters in their own right rather than accented
+// or joined forms, so Unicode has no decomposition for them, with the letters
+// they are usually written as without them.
+const SPELLINGS: &[(char, &str)] = &[
+    ('Æ', "AE"),
+    ('Đ', "D"),
+    ('Ð', "D"),
+    ('Ħ', "H"),
+    ('Ł', "L"),
+    ('Ø', "O"),
+    ('Œ', "OE"),
+    ('ẞ', "SS"),
+    ('Þ', "TH"),
+    ('Ŧ', "T"),
+];
+
+// Spells `c` in plainer characters: uppercased, with the `unicode` feature
+// in compatibility decomposition without combining marks, and with the
+// letters in SPELLINGS spelled out.
+fn decompose(c: char) -> Vec<char> {
+    #[cfg(feature = "unicode")]
+    let decomposed = c.to_uppercase().nfkd().filter(|&d| !is_combining_mark(d));
+    #[cfg(not(feature = "unicode"))]
+    let decomposed = c.to_uppercase();
+
+    decomposed
+        .flat_map(
+            |d| match SPELLINGS.iter().find(|&&(letter, _)| letter == d) {
+                Some((_, spelling)) => spelling.chars().collect(),
+                None => vec![d],
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal_key() {
+        assert_eq!(&[1, 2][..], minimal_key(&[1, 2, 1, 2, 1, 2]));
+        assert_eq!(&[1, 2, 1][..], minimal_key(&[1, 2, 1]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let latin = Alphabet::latin();
+        assert_eq!(Err(CipherError::EmptyKey), Key::parse("", &latin));
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 2, ch: '!' }),
+            Key::parse("HI!", &latin)
+        );
+    }
+
+    #[test]
+    fn test_from_digits_errors() {
+        assert_eq!(Err(CipherError::EmptyKey), Key::from_digits(""));
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 1, ch: 'x' }),
+            Key::from_digits("1x3")
+        );
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 0, ch: '٣' }),
+            Key::from_digits("٣")
+        );
+    }
+
+    #[test]
+    fn test_derive_keeps_letters_of_the_alphabet() {
+        // Й is a letter of its own, not И with an accent.
+        let derived = KeyBuilder::new_in(Alphabet::cyrillic())
+            .derive("мой ключ")
+            .unwrap();
+        assert_eq!("МОЙКЛЮЧ", derived.key.as_str());
+        assert_eq!(vec![RemovedChar { pos: 3, ch: ' ' }], derived.removed);
+
+        // Digits in the alphabet are kept whatever the mapping.
+        let derived = KeyBuilder::new_in(Alphabet::alphanumeric())
+            .with_digits(DigitMapping::Letters)
+            .derive("route 66")
+            .unwrap();
+        assert_eq!("ROUTE66", derived.key.as_str());
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_derive_transliterates() {
+        let derived = KeyBuilder::new()
+            .derive("Straße ﬁnale, déjà vu Ｑ")
+            .unwrap();
+        assert_eq!("STRASSEFINALEDEJAVUQ", derived.key.as_str());
+        assert_eq!(
+            vec![' ', ',', ' ', ' ', ' '],
+            derived.removed.iter().map(|r| r.ch).collect::<Vec<char>>()
+        );
+        assert_eq!(
+            vec![
+                Transliteration {
+                    pos: 15,
+                    ch: 'é',
+                    replacement: "E".to_string()
+                },
+                Transliteration {
+                    pos: 17,
+                    ch: 'à',
+                    replacement: "A".to_string()
+                },
+                Transliteration {
+                    pos: 22,
+                    ch: 'Ｑ',
+                    replacement: "Q".to_string()
+                },
+            ],
+            derived.transliterated
+        );
+
+        let derived = KeyBuilder::new_in(Alphabet::greek())
+            .derive("Καλημέρα")
+            .unwrap();
+        assert_eq!("ΚΑΛΗΜΕΡΑ", derived.key.as_str());
+    }
+
+    #[test]
+    fn test_derive_digits() {
+        let builder = KeyBuilder::new();
+        let derived = builder.derive("a1").unwrap();
+        assert_eq!("A", derived.key.as_str());
+        assert_eq!(vec![RemovedChar { pos: 1, ch: '1' }], derived.removed);
+
+        let derived = builder
+            .with_digits(DigitMapping::Letters)
+            .derive("a1")
+            .unwrap();
+        assert_eq!(&[0, 1], derived.key.shifts());
+        assert!(derived.removed.is_empty());
+
+        // Only the first three digits have letters over a three letter
+        // alphabet.
+        let derived = KeyBuilder::new_in(Alphabet::new("XYZ"))
+            .with_digits(DigitMapping::Letters)
+            .derive("0123")
+            .unwrap();
+        assert_eq!("XYZ", derived.key.as_str());
+        assert_eq!(vec![RemovedChar { pos: 3, ch: '3' }], derived.removed);
+    }
+
+    #[test]
+    fn test_derive_spells_out_letters() {
+        let derived = KeyBuilder::new().derive("Œuvre, Ørsted, Æsir").unwrap();
+        assert_eq!("OEUVREORSTEDAESIR", derived.key.as_str());
+        assert_eq!(
+            vec![('Œ', "OE"), ('Ø', "O"), ('Æ', "AE")],
+            derived
+                .transliterated
+                .iter()
+                .map(|t| (t.ch, t.replacement.as_str()))
+                .collect::<Vec<(char, &str)>>()
+        );
+
+        // Lowercase forms are uppercased first.
+        let derived = KeyBuilder::new().derive("þœ").unwrap();
+        assert_eq!("THOE", derived.key.as_str());
+        assert_eq!(2, derived.transliterated.len());
+    }
+
+    #[test]
+    fn test_derive_errors() {
+        assert_eq!(Err(CipherError::EmptyKey), KeyBuilder::new().derive(""));
+        assert_eq!(
+            Err(CipherError::EmptyKey),
+            KeyBuilder::new().derive("2024 !?")
+        );
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        let latin = Alphabet::latin();
+        // A repeated letter doesn't make a key any less likely to be drawn.
+        for key in ["AA", "AB"] {
+            let key = Key::parse(key, &latin).unwrap();
+            assert_eq!(2.0 * 26f64.log2(), key.entropy_bits(&latin));
+        }
+
+        let key = Key::from_digits("0123").unwrap();
+        assert_eq!(4.0 * 26f64.log2(), key.entropy_bits(&latin));
+        assert_eq!(4.0, key.entropy_bits(&Alphabet::new("XY")));
+        assert_eq!(0.0, key.entropy_bits(&Alphabet::new("X")));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_generate() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let greek = Alphabet::greek();
+        let key = Key::generate_in(20, &greek, &mut rng);
+        assert_eq!(20, key.shifts().len());
+        assert_eq!(Ok(&key), Key::parse(key.as_str(), &greek).as_ref());
+
+        assert_eq!(Err(CipherError::EmptyKey), Key::try_generate(0, &mut rng));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_generate_is_uniform() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        // Over three letters, 2^32 isn't a multiple of the alphabet size.
+        let alphabet = Alphabet::new("XYZ");
+        let key = Key::generate_in(30_000, &alphabet, &mut StdRng::seed_from_u64(1));
+        let mut counts = [0; 3];
+        for &i in key.shifts() {
+            counts[i as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (9_500..10_500).contains(&c)));
+    }
+}
@@ -1,127 +1,80 @@
-// The Vigenère Cipher encrypts a plain text file by performing
-// a rotation of each character in the plain.  The rotation depends
-// on the key, and every character in the key rotates the corresponding
-// plain text value by that amount.  If the key is shorter than the
-// plain text, then key is cycled.
-
-// subtract 65 to convert to the alphabetic position (A = 0, B = 1.. )
-fn to_alpha_index(c: &char) -> u8 {
-    (*c as u8) - 65
-}
-
-// convert alphabetic position to a char
-fn to_char(i: u8) -> char {
-    (i + 65) as char
-}
-
-// takes a numeric value that represents a plain text letter  and an amount to rotate
-// if index = 90 which is Z and amt = 1, than 65 which is A should
-// be returned (wraps)
-fn rotate_index(i: u8, amt: u8) -> u8 {
-    (i + amt) % 26
-}
-
-// Used by decrypt to undo rotate_index()
-fn reverse_rotate_index(i: u8, amt: u8) -> u8 {
-    let a = (i as i32 - amt as i32) as f32;
-    let n = 26 as f32;
-
-    // This is the definition of modulo given by Donald Knuth.
-    // I use this definition instead of the builtin mod % operator
-    // because I want the result to be positive.
-    // https://torstencurdt.com/tech/posts/modulo-of-negative-numbers
-    (a - n * (a / n).floor()) as u8
-}
-
-fn enc(key: &str, val: &str) -> String {
-    let key_vec = key.chars().collect::<Vec<char>>();
-    let key_length = key_vec.len();
-
-    // Create an array where each letter is converted to
-    // it's numeric position: [A, B, C] becomes [0, 1, 2].
-    let alpha_index = val.chars().map(|c| to_alpha_index(&c));
-
-    // Allocate some space to return the value on the stack.
-    let mut return_val = String::from("");
-
-    // Iterate over the numeric positions and perform the rotation.
-    for (i, a_i) in alpha_index.enumerate() {
-        // Cycle over the key and mod by the length
-        // if a key for example is half the size of the plain text
-        // then each key value will be used twice.
-        let key_char: char = key_vec[i % key_length];
-
-        // Find the amount to shift by given a key char.
-        let shift_amt: u8 = to_alpha_index(&key_char);
-
-        // Apply the rotation.
-        let index = rotate_index(a_i, shift_amt);
-
-        // Convert back to a char.
-        let enc_char = to_char(index);
-
-        return_val.push(enc_char);
-    }
-    return_val
-}
-
-fn dec(key: &str, val: &str) -> String {
-    let key_vec = key.chars().collect::<Vec<char>>();
-    let key_length = key_vec.len();
-    let alpha_index = val.chars().map(|c| to_alpha_index(&c));
-
-    let mut return_val = String::from("");
-    for (i, a_i) in alpha_index.enumerate() {
-        let key_char: char = key_vec[i % key_length];
-        let shift_amt: u8 = to_alpha_index(&key_char);
-
-        let index = reverse_rotate_index(a_i, shift_amt);
-        let enc_char = to_char(index);
-
-        return_val.push(enc_char);
-    }
-
-    return_val
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_to_alpha_pos() {
-        assert_eq!(0, to_alpha_index(&'A'));
-    }
-
-    #[test]
-    fn test_to_char() {
-        assert_eq!('A', to_char(0));
-    }
-
-    #[test]
-    fn test_rotate_index() {
-        assert_eq!(0, rotate_index(25, 1));
-    }
-
-    #[test]
-    fn test_enc() {
-        let cipher_key = "DUH";
-        let plain_text = "CRYPTO";
-        assert_eq!("FLFSNV", enc(&cipher_key, &plain_text));
-
-        let cipher_key = "DUH";
-        let plain_text = "THEYDRINKTHETEA";
-        assert_eq!("WBLBXYLHRWBLWYH", enc(&cipher_key, &plain_text));
-    }
-
-    #[test]
-    fn test_dec() {
-        let cipher_key = "DUH";
-        let plain_text = "FLFSNV";
-        assert_eq!("CRYPTO", dec(&cipher_key, &plain_text));
-
-        let cipher_key = "DUH";
-        let plain_text = "WBLBXYLHRWBLWYH";
-        assert_eq!("THEYDRINKTHETEA", dec(&cipher_key, &plain_text));
-    }
-}
+//! The Vigenère Cipher encrypts a plain text file by performing
+//! a rotation of each character in the plain.  The rotation depends
+//! on the key, and every character in the key rotates the corresponding
+//! plain text value by that amount.  If the key is shorter than the
+//! plain text, then key is cycled.
+//!
+//! ```
+//! use vigenere_cipher::VigenereCipher;
+//!
+//! let cipher = VigenereCipher::new("DUH");
+//! assert_eq!("FLFSNV", cipher.encrypt("CRYPTO"));
+//! assert_eq!("CRYPTO", cipher.decrypt("FLFSNV"));
+//! ```
+//!
+//! # Module layout
+//!
+//! The ciphers and the types they are configured with are re-exported from
+//! the root, so callers should import them from `vigenere_cipher::` directly
+//! rather than reaching into submodules.  The cryptanalysis routines are
+//! imported from [`analysis`].
+//!
+//! - [`analysis`]: cryptanalysis of Vigenère cipher text, such as
+//!   [`fn@analysis::kasiski`] and [`analysis::friedman`] for estimating the key
+//!   length, [`analysis::break_vigenere`] for recovering the key, and the
+//!   [`analysis::LanguageModel`]s it ranks candidates with, and
+//!   [`analysis::break_repeating_xor`] for repeating-key XOR.
+//! - [`alphabet`]: [`Alphabet`], the ordered set of characters a cipher
+//!   rotates within.
+//! - [`autokey`]: [`AutokeyCipher`], the variant whose key continues with the
+//!   plain text.
+//! - [`bytes`]: [`ByteCipher`], the Vigenère cipher over arbitrary bytes by
+//!   addition modulo 256 or XOR.
+//! - [`cipher`]: the [`VigenereCipher`] type, the [`Tableau`]s that turn it
+//!   into a Beaufort cipher, and the rotation helpers they are built on.
+//! - [`error`]: [`CipherError`], returned by the `try_` variants of every
+//!   operation instead of panicking.
+//! - [`key`]: [`Key`], a key validated against an alphabet, which every
+//!   cipher can be created from, and [`KeyBuilder`], which derives one from a
+//!   passphrase.  `Key::generate` picks a random key, with the `rand`
+//!   feature, which is on by default.
+//! - [`porta`]: [`PortaCipher`], the reciprocal cipher whose key letters
+//!   select one of thirteen paired alphabets.
+//! - [`quagmire`]: [`QuagmireCipher`], the Quagmire I–IV variants with
+//!   keyword-mixed alphabets.
+//! - [`running_key`]: [`RunningKeyCipher`], the variant whose key is drawn
+//!   from a long text.
+//! - [`stream`]: [`VigenereReader`] and [`VigenereWriter`], for enciphering
+//!   text too large to hold in memory.
+//! - `unicode`: `GraphemeAlphabet` and `GraphemeCipher`, for alphabets whose
+//!   letters are grapheme clusters, in a chosen `Normalization`.  Requires
+//!   the `unicode` feature, which is on by default.
+
+pub mod alphabet;
+pub mod analysis;
+pub mod autokey;
+mod bulk;
+pub mod bytes;
+pub mod cipher;
+pub mod error;
+pub mod key;
+mod modular;
+pub mod porta;
+pub mod quagmire;
+pub mod running_key;
+pub mod stream;
+#[cfg(feature = "unicode")]
+pub mod unicode;
+
+pub use alphabet::Alphabet;
+pub use autokey::AutokeyCipher;
+pub use bytes::{ByteCipher, ByteOp};
+pub use cipher::{NonAlpha, Tableau, VigenereCipher};
+pub use error::CipherError;
+pub use key::{DerivedKey, DigitMapping, Key, KeyBuilder, RemovedChar, Transliteration};
+pub use porta::PortaCipher;
+pub use quagmire::{Quagmire, QuagmireCipher};
+pub use running_key::RunningKeyCipher;
+pub use stream::{VigenereReader, VigenereWriter};
+#[cfg(feature = "unicode")]
+pub use unicode::{GraphemeAlphabet, GraphemeCipher, Normalization};
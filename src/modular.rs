@@ -0,0 +1,113 @@
+//! Exact modular arithmetic on alphabet positions.
+//!
+//! Every cipher rotates positions within an alphabet of `n` characters, for
+//! any `n` up to `u32::MAX`.  Sums and differences are worked out in 64 bits,
+//! where they can't overflow, and reduced with `rem_euclid`, which unlike `%`
+//! is never negative.  The operands don't have to be reduced already.
+
+// (a + b) mod n.
+pub(crate) fn add_mod(a: u32, b: u32, n: u32) -> u32 {
+    (u64::from(a) + u64::from(b)).rem_euclid(u64::from(n)) as u32
+}
+
+// (a - b) mod n, wrapping around to the top of the alphabet rather than going
+// negative.
+pub(crate) fn sub_mod(a: u32, b: u32, n: u32) -> u32 {
+    (i64::from(a) - i64::from(b)).rem_euclid(i64::from(n)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::{self, Alphabet};
+    use crate::{AutokeyCipher, Tableau, VigenereCipher};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_round_trip_largest_alphabet() {
+        // Every Unicode scalar value, the most characters an alphabet can
+        // have, with a key from the top of it.
+        let alphabet = Alphabet::new(
+            &(0..=char::MAX as u32)
+                .filter_map(char::from_u32)
+                .collect::<String>(),
+        );
+        let n = alphabet.size() as u32;
+        let key = (n - 3..n).map(|i| alphabet.char_at(i)).collect::<String>();
+        let text = (n - 5..n)
+            .chain(0..5)
+            .map(|i| alphabet.char_at(i))
+            .collect::<String>();
+
+        for tableau in [
+            Tableau::Vigenere,
+            Tableau::Beaufort,
+            Tableau::VariantBeaufort,
+        ] {
+            let cipher = VigenereCipher::new_in(&key, alphabet.clone()).with_tableau(tableau);
+            assert_eq!(text, cipher.decrypt(&cipher.encrypt(&text)));
+        }
+        let cipher = AutokeyCipher::new_in(&key, alphabet);
+        assert_eq!(text, cipher.decrypt(&cipher.encrypt(&text)));
+    }
+
+    #[test]
+    fn test_wraps() {
+        assert_eq!(0, add_mod(25, 1, 26));
+        assert_eq!(25, sub_mod(0, 1, 26));
+        assert_eq!(u32::MAX - 1, add_mod(u32::MAX - 1, u32::MAX, u32::MAX));
+        // Too large to be represented exactly as an f32.
+        assert_eq!(100_000_006, sub_mod(0, 1, 100_000_007));
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_wide_arithmetic(a: u32, b: u32, n in 1..=u32::MAX) {
+            let wide = |x: i128| x.rem_euclid(i128::from(n)) as u32;
+            prop_assert_eq!(wide(i128::from(a) + i128::from(b)), add_mod(a, b, n));
+            prop_assert_eq!(wide(i128::from(a) - i128::from(b)), sub_mod(a, b, n));
+        }
+
+        #[test]
+        fn test_sub_undoes_add(n in 1..=u32::MAX, a: u32, b: u32) {
+            let a = a % n;
+            prop_assert_eq!(a, sub_mod(add_mod(a, b, n), b, n));
+            prop_assert_eq!(a, add_mod(sub_mod(a, b, n), b, n));
+        }
+
+        #[test]
+        fn test_round_trip_any_alphabet_size(
+            (n, key, text) in (1..=20_992u32)
+                .prop_flat_map(|n| (Just(n), vec(0..n, 1..16), vec(0..n, 0..64)))
+        ) {
+            let alphabet = alphabet::sized(n);
+            let key = key.iter().map(|&i| alphabet.char_at(i)).collect::<String>();
+            let text = text.iter().map(|&i| alphabet.char_at(i)).collect::<String>();
+
+            for tableau in [Tableau::Vigenere, Tableau::Beaufort, Tableau::VariantBeaufort] {
+                let cipher = VigenereCipher::new_in(&key, alphabet.clone()).with_tableau(tableau);
+                prop_assert_eq!(&text, &cipher.decrypt(&cipher.encrypt(&text)));
+            }
+            let cipher = AutokeyCipher::new_in(&key, alphabet);
+            prop_assert_eq!(&text, &cipher.decrypt(&cipher.encrypt(&text)));
+        }
+
+        #[test]
+        fn test_round_trip_near_the_top(
+            (n, letters, key) in (u32::MAX - 1024..=u32::MAX).prop_flat_map(|n| {
+                (Just(n), vec(n - 1024..n, 1..64), vec(u32::MAX - 1024..=u32::MAX, 1..16))
+            })
+        ) {
+            // Where a + b and a - b no longer fit in a u32.
+            for tableau in [Tableau::Vigenere, Tableau::Beaufort, Tableau::VariantBeaufort] {
+                let (encrypt, decrypt) = (tableau.rotation(false), tableau.rotation(true));
+                for (&i, &k) in letters.iter().zip(key.iter().cycle()) {
+                    let c = encrypt(i, k, n);
+                    prop_assert!(c < n);
+                    prop_assert_eq!(i, decrypt(c, k, n));
+                }
+            }
+        }
+    }
+}
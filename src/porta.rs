@@ -0,0 +1,176 @@
+//! The Porta cipher, Giovan Battista della Porta's reciprocal cipher.
+//!
+//! The alphabet is split into two halves and each pair of key letters
+//! (`A`/`B`, `C`/`D`, ..) selects one of thirteen tableau rows that swap a
+//! letter in the first half with one in the second.  Every row undoes
+//! itself, so the same operation both encrypts and decrypts.
+
+use crate::alphabet::Alphabet;
+use crate::cipher::{reverse_rotate_index, rotate_index, transform, NonAlpha, RepeatingKey};
+use crate::error::CipherError;
+use crate::key::Key;
+
+// The row for key letter `amt` slides the second half of the alphabet one
+// place further along the first for every pair of key letters.
+fn porta_index(i: u32, amt: u32, n: u32) -> u32 {
+    let half = n / 2;
+    let k = amt / 2;
+    if i < half {
+        half + rotate_index(i, k, half)
+    } else {
+        reverse_rotate_index(i - half, k, half)
+    }
+}
+
+/// A Porta cipher bound to a key.
+///
+/// The key must be made of characters of the cipher's [`Alphabet`], which is
+/// `A`–`Z` unless the cipher was created with [`new_in`](Self::new_in), and
+/// the alphabet must have an even number of characters.  Text is handled the
+/// same way as by [`VigenereCipher`](crate::VigenereCipher), including
+/// [`with_non_alpha`](Self::with_non_alpha).
+///
+/// ```
+/// use vigenere_cipher::PortaCipher;
+///
+/// let cipher = PortaCipher::new("FORTIFICATION");
+/// let cipher_text = cipher.apply("DEFENDTHEEASTWALLOFTHECASTLE");
+/// assert_eq!("SYNNJSCVRNRLAHUTUKUCVRYRLANY", cipher_text);
+/// assert_eq!("DEFENDTHEEASTWALLOFTHECASTLE", cipher.apply(&cipher_text));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortaCipher {
+    key: Key,
+    alphabet: Alphabet,
+    non_alpha: NonAlpha,
+}
+
+impl PortaCipher {
+    /// Creates a cipher that uses `key` for every message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty or contains anything other than `A`–`Z`.
+    /// Use [`try_new`](Self::try_new) to handle that instead.
+    pub fn new(key: &str) -> PortaCipher {
+        PortaCipher::try_new(key).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that uses `key` for every message, or reports why
+    /// `key` can't be used.
+    pub fn try_new(key: &str) -> Result<PortaCipher, CipherError> {
+        PortaCipher::try_new_in(key, Alphabet::default())
+    }
+
+    /// Creates a cipher that pairs up the characters of `alphabet` instead
+    /// of `A`–`Z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alphabet` has an odd number of characters, or if `key` is
+    /// empty or contains a character outside `alphabet`.  Use
+    /// [`try_new_in`](Self::try_new_in) to handle that instead.
+    pub fn new_in(key: &str, alphabet: Alphabet) -> PortaCipher {
+        PortaCipher::try_new_in(key, alphabet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that pairs up the characters of `alphabet`, or
+    /// reports why `key` or `alphabet` can't be used.
+    pub fn try_new_in(key: &str, alphabet: Alphabet) -> Result<PortaCipher, CipherError> {
+        if !alphabet.size().is_multiple_of(2) {
+            return Err(CipherError::OddAlphabetSize {
+                size: alphabet.size(),
+            });
+        }
+
+        Ok(PortaCipher {
+            key: Key::parse(key, &alphabet)?,
+            alphabet,
+            non_alpha: NonAlpha::default(),
+        })
+    }
+
+    /// Sets how characters outside the alphabet are handled.
+    pub fn with_non_alpha(mut self, non_alpha: NonAlpha) -> PortaCipher {
+        self.non_alpha = non_alpha;
+        self
+    }
+
+    /// Returns how characters outside the alphabet are handled.
+    pub fn non_alpha(&self) -> NonAlpha {
+        self.non_alpha
+    }
+
+    /// Returns the key this cipher was created with.
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    /// Returns the alphabet this cipher pairs characters within.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Encrypts or decrypts `val`; the Porta cipher is its own inverse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn apply(&self, val: &str) -> String {
+        self.try_apply(val).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts or decrypts `val`, or reports the first character that can't
+    /// be handled.
+    pub fn try_apply(&self, val: &str) -> Result<String, CipherError> {
+        let mut keystream = RepeatingKey {
+            shifts: self.key.shifts(),
+            pos: 0,
+            n: self.alphabet.size() as u32,
+            rotate: porta_index,
+        };
+        transform(&self.alphabet, self.non_alpha, val, &mut keystream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_porta_index() {
+        // Key letters A and B share the first row, which swaps the halves.
+        assert_eq!(13, porta_index(0, 0, 26));
+        assert_eq!(13, porta_index(0, 1, 26));
+        assert_eq!(0, porta_index(13, 1, 26));
+        // The Z row swaps A with Z.
+        assert_eq!(25, porta_index(0, 25, 26));
+        assert_eq!(0, porta_index(25, 25, 26));
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        let cipher = PortaCipher::new("LEMON").with_non_alpha(NonAlpha::Preserve);
+        let plain_text = "Attack at dawn!";
+        let cipher_text = cipher.apply(plain_text);
+        assert_eq!("Seauvp pa xtel!", cipher_text);
+        assert_eq!(plain_text, cipher.apply(&cipher_text));
+    }
+
+    #[test]
+    fn test_other_alphabets() {
+        let cipher = PortaCipher::new_in("ΚΛΕΙΔΙ", Alphabet::greek());
+        let plain_text = "ΑΛΦΑΒΗΤΟ";
+        assert_eq!(plain_text, cipher.apply(&cipher.apply(plain_text)));
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        assert_eq!(Err(CipherError::EmptyKey), PortaCipher::try_new(""));
+        assert_eq!(
+            Err(CipherError::OddAlphabetSize { size: 33 }),
+            PortaCipher::try_new_in("КЛЮЧ", Alphabet::cyrillic())
+        );
+    }
+}
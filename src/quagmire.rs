@@ -0,0 +1,414 @@
+//! The Quagmire ciphers: Vigenère ciphers with keyword-mixed alphabets.
+//!
+//! The American Cryptogram Association names four variants, by which of the
+//! plain and cipher alphabets are mixed with a keyword.  Each row of the
+//! tableau is the cipher alphabet slid along so that the key letter sits
+//! beneath the indicator letter of the plain alphabet; a letter is enciphered
+//! by finding it in the plain alphabet and reading the letter beneath it in
+//! the row for the current key letter.
+
+use crate::alphabet::Alphabet;
+use crate::cipher::{reverse_rotate_index, rotate_index, transform, Keystream, NonAlpha};
+use crate::error::CipherError;
+use crate::key::Key;
+
+/// Which Quagmire cipher to use, with the keywords its alphabets are mixed
+/// by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quagmire<'a> {
+    /// A mixed plain alphabet over a straight cipher alphabet.
+    I { keyword: &'a str },
+    /// A straight plain alphabet over a mixed cipher alphabet.
+    II { keyword: &'a str },
+    /// The same mixed alphabet for both plain and cipher text.
+    III { keyword: &'a str },
+    /// Plain and cipher alphabets mixed by different keywords.
+    IV {
+        plain_keyword: &'a str,
+        cipher_keyword: &'a str,
+    },
+}
+
+// The Quagmire keystream.  The tables convert between a character's position
+// in the straight alphabet, which is what transform() works in, and its
+// position in the plain or cipher alphabet.
+struct Rows<'a> {
+    shifts: &'a [u32],
+    pos: usize,
+    n: u32,
+    indicator: u32,
+    plain_pos: &'a [u32],
+    plain_at: &'a [u32],
+    cipher_pos: &'a [u32],
+    cipher_at: &'a [u32],
+    decrypt: bool,
+}
+
+impl Keystream for Rows<'_> {
+    fn letter(&mut self, i: u32) -> u32 {
+        // How far the key letter's row is slid along the plain alphabet.
+        let key_letter = self.shifts[self.pos % self.shifts.len()] % self.n;
+        let offset =
+            reverse_rotate_index(self.cipher_pos[key_letter as usize], self.indicator, self.n);
+        self.pos += 1;
+
+        if self.decrypt {
+            let column = reverse_rotate_index(self.cipher_pos[i as usize], offset, self.n);
+            self.plain_at[column as usize]
+        } else {
+            let column = rotate_index(self.plain_pos[i as usize], offset, self.n);
+            self.cipher_at[column as usize]
+        }
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+    }
+}
+
+// Position in `straight` of each character of `mixed`, and the inverse.
+fn tables(straight: &Alphabet, mixed: &Alphabet) -> (Vec<u32>, Vec<u32>) {
+    let at = mixed
+        .chars()
+        .iter()
+        .map(|&c| straight.index_of(c).unwrap_or(0))
+        .collect::<Vec<u32>>();
+
+    let mut pos = vec![0; at.len()];
+    for (p, &i) in at.iter().enumerate() {
+        pos[i as usize] = p as u32;
+    }
+    (pos, at)
+}
+
+/// A Quagmire cipher bound to a key.
+///
+/// The key and alphabet keywords must be made of characters of the cipher's
+/// [`Alphabet`], which is `A`–`Z` unless the cipher was created with
+/// [`new_in`](Self::new_in).  The indicator defaults to `A`.  Text is handled
+/// the same way as by [`VigenereCipher`](crate::VigenereCipher), including
+/// [`with_non_alpha`](Self::with_non_alpha).
+///
+/// ```
+/// use vigenere_cipher::{Quagmire, QuagmireCipher};
+///
+/// let cipher = QuagmireCipher::new(Quagmire::III { keyword: "AUTOMOBILE" }, "HIGHWAY");
+/// let cipher_text = cipher.encrypt("ATTACKATDAWN");
+/// assert_eq!("ATTACKATDAWN", cipher.decrypt(&cipher_text));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuagmireCipher {
+    key: Key,
+    alphabet: Alphabet,
+    plain: Alphabet,
+    cipher: Alphabet,
+    indicator: char,
+    non_alpha: NonAlpha,
+}
+
+impl QuagmireCipher {
+    /// Creates a cipher of the given variant that uses `key` for every
+    /// message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty, or if it or a keyword contains anything
+    /// other than `A`–`Z`.  Use [`try_new`](Self::try_new) to handle that
+    /// instead.
+    pub fn new(variant: Quagmire<'_>, key: &str) -> QuagmireCipher {
+        QuagmireCipher::try_new(variant, key).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher of the given variant, or reports why `key` or one of
+    /// the keywords can't be used.
+    pub fn try_new(variant: Quagmire<'_>, key: &str) -> Result<QuagmireCipher, CipherError> {
+        QuagmireCipher::try_new_in(variant, key, Alphabet::default())
+    }
+
+    /// Creates a cipher whose plain and cipher alphabets are mixed from
+    /// `alphabet` instead of `A`–`Z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty, or if it or a keyword contains a character
+    /// outside `alphabet`.  Use [`try_new_in`](Self::try_new_in) to handle
+    /// that instead.
+    pub fn new_in(variant: Quagmire<'_>, key: &str, alphabet: Alphabet) -> QuagmireCipher {
+        QuagmireCipher::try_new_in(variant, key, alphabet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher whose alphabets are mixed from `alphabet`, or reports
+    /// why `key` or one of the keywords can't be used with it.
+    pub fn try_new_in(
+        variant: Quagmire<'_>,
+        key: &str,
+        alphabet: Alphabet,
+    ) -> Result<QuagmireCipher, CipherError> {
+        let key = Key::parse(key, &alphabet)?;
+        let (plain, cipher) = match variant {
+            Quagmire::I { keyword } => (alphabet.keyed(keyword)?, alphabet.clone()),
+            Quagmire::II { keyword } => (alphabet.clone(), alphabet.keyed(keyword)?),
+            Quagmire::III { keyword } => {
+                let mixed = alphabet.keyed(keyword)?;
+                (mixed.clone(), mixed)
+            }
+            Quagmire::IV {
+                plain_keyword,
+                cipher_keyword,
+            } => (
+                alphabet.keyed(plain_keyword)?,
+                alphabet.keyed(cipher_keyword)?,
+            ),
+        };
+
+        Ok(QuagmireCipher {
+            key,
+            indicator: alphabet.char_at(0),
+            alphabet,
+            plain,
+            cipher,
+            non_alpha: NonAlpha::default(),
+        })
+    }
+
+    /// Sets the letter of the plain alphabet that the key letters are placed
+    /// beneath.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indicator` is not in the alphabet.  Use
+    /// [`try_with_indicator`](Self::try_with_indicator) to handle that
+    /// instead.
+    pub fn with_indicator(self, indicator: char) -> QuagmireCipher {
+        self.try_with_indicator(indicator)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Sets the letter of the plain alphabet that the key letters are placed
+    /// beneath, or reports that it is not in the alphabet.
+    pub fn try_with_indicator(mut self, indicator: char) -> Result<QuagmireCipher, CipherError> {
+        if !self.alphabet.contains(indicator) {
+            return Err(CipherError::InvalidIndicator { ch: indicator });
+        }
+        self.indicator = indicator;
+        Ok(self)
+    }
+
+    /// Sets how characters outside the alphabet are handled.
+    pub fn with_non_alpha(mut self, non_alpha: NonAlpha) -> QuagmireCipher {
+        self.non_alpha = non_alpha;
+        self
+    }
+
+    /// Returns how characters outside the alphabet are handled.
+    pub fn non_alpha(&self) -> NonAlpha {
+        self.non_alpha
+    }
+
+    /// Returns the key this cipher was created with.
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    /// Returns the letter of the plain alphabet the key letters are placed
+    /// beneath.
+    pub fn indicator(&self) -> char {
+        self.indicator
+    }
+
+    /// Returns the plain text alphabet.
+    pub fn plain_alphabet(&self) -> &Alphabet {
+        &self.plain
+    }
+
+    /// Returns the cipher text alphabet.
+    pub fn cipher_alphabet(&self) -> &Alphabet {
+        &self.cipher
+    }
+
+    /// Encrypts `plain_text`, returning the cipher text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plain_text` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn encrypt(&self, plain_text: &str) -> String {
+        self.try_encrypt(plain_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Decrypts `cipher_text`, returning the plain text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cipher_text` contains a character that is rejected under
+    /// [`NonAlpha::Reject`].
+    pub fn decrypt(&self, cipher_text: &str) -> String {
+        self.try_decrypt(cipher_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts `plain_text`, or reports the first character that can't be
+    /// handled.
+    pub fn try_encrypt(&self, plain_text: &str) -> Result<String, CipherError> {
+        self.transform(plain_text, false)
+    }
+
+    /// Decrypts `cipher_text`, or reports the first character that can't be
+    /// handled.
+    pub fn try_decrypt(&self, cipher_text: &str) -> Result<String, CipherError> {
+        self.transform(cipher_text, true)
+    }
+
+    fn transform(&self, val: &str, decrypt: bool) -> Result<String, CipherError> {
+        let (plain_pos, plain_at) = tables(&self.alphabet, &self.plain);
+        let (cipher_pos, cipher_at) = tables(&self.alphabet, &self.cipher);
+        let indicator = self.plain.index_of(self.indicator).unwrap_or(0);
+
+        let mut keystream = Rows {
+            shifts: self.key.shifts(),
+            pos: 0,
+            n: self.alphabet.size() as u32,
+            indicator,
+            plain_pos: &plain_pos,
+            plain_at: &plain_at,
+            cipher_pos: &cipher_pos,
+            cipher_at: &cipher_at,
+            decrypt,
+        };
+        transform(&self.alphabet, self.non_alpha, val, &mut keystream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VigenereCipher;
+
+    #[test]
+    fn test_straight_alphabets_are_vigenere() {
+        let vigenere = VigenereCipher::new("LEMON");
+        for variant in [
+            Quagmire::I { keyword: "" },
+            Quagmire::II { keyword: "" },
+            Quagmire::III { keyword: "" },
+            Quagmire::IV {
+                plain_keyword: "",
+                cipher_keyword: "",
+            },
+        ] {
+            let cipher = QuagmireCipher::new(variant, "LEMON");
+            assert_eq!(
+                vigenere.encrypt("ATTACKATDAWN"),
+                cipher.encrypt("ATTACKATDAWN")
+            );
+        }
+    }
+
+    #[test]
+    fn test_quagmire_i() {
+        // The plain alphabet is KEYABCDFGH..., so under key B with indicator
+        // A, A becomes B, and K (three letters before A) becomes Y.
+        let cipher = QuagmireCipher::new(Quagmire::I { keyword: "KEY" }, "B");
+        assert_eq!("YB", cipher.encrypt("KA"));
+        assert_eq!("KA", cipher.decrypt("YB"));
+    }
+
+    #[test]
+    fn test_quagmire_ii() {
+        // The cipher alphabet is KEYABCDFGH..., slid so K is under A.
+        let cipher = QuagmireCipher::new(Quagmire::II { keyword: "KEY" }, "K");
+        assert_eq!("KEYA", cipher.encrypt("ABCD"));
+        assert_eq!("ABCD", cipher.decrypt("KEYA"));
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let variants = [
+            Quagmire::I {
+                keyword: "SPRINGFEVER",
+            },
+            Quagmire::II {
+                keyword: "SPRINGFEVER",
+            },
+            Quagmire::III {
+                keyword: "AUTOMOBILE",
+            },
+            Quagmire::IV {
+                plain_keyword: "SENSORY",
+                cipher_keyword: "PERCEPTIVE",
+            },
+        ];
+        for variant in variants {
+            let cipher = QuagmireCipher::new(variant, "EXTRAORDINARY")
+                .with_indicator('E')
+                .with_non_alpha(NonAlpha::Preserve);
+            let plain_text = "The quick brown fox jumps over the lazy dog.";
+            let cipher_text = cipher.encrypt(plain_text);
+            assert_ne!(plain_text, cipher_text);
+            assert_eq!(plain_text, cipher.decrypt(&cipher_text), "{:?}", variant);
+        }
+    }
+
+    #[test]
+    fn test_indicator_lines_up_key() {
+        // Whatever the alphabets, the indicator letter enciphers to the key
+        // letter.
+        let cipher = QuagmireCipher::new(
+            Quagmire::IV {
+                plain_keyword: "SENSORY",
+                cipher_keyword: "PERCEPTIVE",
+            },
+            "KEY",
+        )
+        .with_indicator('R');
+        assert_eq!("KEY", cipher.encrypt("RRR"));
+    }
+
+    #[test]
+    fn test_kryptos() {
+        // The first two sections of Jim Sanborn's Kryptos sculpture are
+        // Quagmire III ciphers over the alphabet mixed by KRYPTOS, with the
+        // key letters placed beneath its first letter.
+        let cipher = QuagmireCipher::new(Quagmire::III { keyword: "KRYPTOS" }, "PALIMPSEST")
+            .with_indicator('K');
+        assert_eq!(
+            "EMUFPHZLRFAXYUSDJKZLDKRNSHGNFIVJYQTQUXQBQVYUVLLTREVJYQTMKYRDMFD",
+            cipher.encrypt("BETWEENSUBTLESHADINGANDTHEABSENCEOFLIGHTLIESTHENUANCEOFIQLUSION")
+        );
+
+        let cipher = QuagmireCipher::new(Quagmire::III { keyword: "KRYPTOS" }, "ABSCISSA")
+            .with_indicator('K');
+        assert_eq!(
+            "ITWASTOTALLYINVISIBLEHOWSTHATPO",
+            cipher.decrypt("VFPJUDEEHZWETZYVGWHKKQETGFQJNCE")
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        // A bad keyword is told apart from a bad key.
+        assert_eq!(
+            Err(CipherError::InvalidKeywordChar { pos: 2, ch: '1' }),
+            QuagmireCipher::try_new(Quagmire::III { keyword: "AB1" }, "KEY")
+        );
+        assert_eq!(
+            Err(CipherError::InvalidKeywordChar { pos: 0, ch: 'k' }),
+            QuagmireCipher::try_new(
+                Quagmire::IV {
+                    plain_keyword: "KEY",
+                    cipher_keyword: "key",
+                },
+                "KEY"
+            )
+        );
+        assert_eq!(
+            Err(CipherError::InvalidKeyChar { pos: 1, ch: '1' }),
+            QuagmireCipher::try_new(Quagmire::III { keyword: "KEY" }, "K1")
+        );
+        assert_eq!(
+            Err(CipherError::InvalidIndicator { ch: 'a' }),
+            QuagmireCipher::new(Quagmire::I { keyword: "KEY" }, "KEY").try_with_indicator('a')
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! The running-key variant of the Vigenère cipher.
+//!
+//! Rather than repeating a short key, the key is taken from a long text such
+//! as a page of a book that both sides have a copy of, starting at an agreed
+//! offset.  The key is never reused, so a message can be no longer than the
+//! text left after the offset.
+
+use crate::alphabet::Alphabet;
+use crate::cipher::{key_positions_needed, transform, NonAlpha, RepeatingKey, Tableau};
+use crate::error::CipherError;
+use crate::key::Key;
+
+/// A running-key cipher drawing its key from a text.
+///
+/// The key text is normalized to the cipher's [`Alphabet`] (`A`–`Z` unless
+/// the cipher was created with [`new_in`](Self::new_in)): lowercase letters
+/// are uppercased and everything else is dropped.  The offset counts the
+/// characters of the normalized key.  Text is handled the same way as by
+/// [`VigenereCipher`](crate::VigenereCipher), including
+/// [`with_non_alpha`](Self::with_non_alpha) and
+/// [`with_tableau`](Self::with_tableau).
+///
+/// ```
+/// use vigenere_cipher::{RunningKeyCipher, VigenereCipher};
+///
+/// let book = "The quick brown fox jumps over the lazy dog.";
+/// let cipher = RunningKeyCipher::new(book, 3);
+/// assert_eq!(
+///     VigenereCipher::new("QUICKBROWNFO").encrypt("ATTACKATDAWN"),
+///     cipher.encrypt("ATTACKATDAWN")
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunningKeyCipher {
+    key: Key,
+    alphabet: Alphabet,
+    non_alpha: NonAlpha,
+    tableau: Tableau,
+}
+
+impl RunningKeyCipher {
+    /// Creates a cipher whose key is `key_text`, starting `offset` letters
+    /// in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key_text` has no letters after `offset`.  Use
+    /// [`try_new`](Self::try_new) to handle that instead.
+    pub fn new(key_text: &str, offset: usize) -> RunningKeyCipher {
+        RunningKeyCipher::try_new(key_text, offset).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher whose key is `key_text`, starting `offset` letters
+    /// in, or reports why that can't be used as a key.
+    pub fn try_new(key_text: &str, offset: usize) -> Result<RunningKeyCipher, CipherError> {
+        RunningKeyCipher::try_new_in(key_text, offset, Alphabet::default())
+    }
+
+    /// Creates a cipher that rotates characters within `alphabet` instead of
+    /// `A`–`Z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key_text` has no characters of `alphabet` after `offset`.
+    /// Use [`try_new_in`](Self::try_new_in) to handle that instead.
+    pub fn new_in(key_text: &str, offset: usize, alphabet: Alphabet) -> RunningKeyCipher {
+        RunningKeyCipher::try_new_in(key_text, offset, alphabet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that rotates characters within `alphabet`, or
+    /// reports why `key_text` can't be used as a key with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CipherError::EmptyKey`] if `key_text` has no characters of
+    /// `alphabet`, and [`CipherError::KeyTooShort`] if it has no more than
+    /// `offset` of them.
+    pub fn try_new_in(
+        key_text: &str,
+        offset: usize,
+        alphabet: Alphabet,
+    ) -> Result<RunningKeyCipher, CipherError> {
+        let normalized = key_text
+            .chars()
+            .filter_map(|c| alphabet.index_ignoring_case(c))
+            .map(|i| alphabet.char_at(i))
+            .collect::<Vec<char>>();
+        // A text with no letters at all is an empty key at any offset.
+        if !normalized.is_empty() && offset >= normalized.len() {
+            return Err(CipherError::KeyTooShort {
+                needed: offset + 1,
+                available: normalized.len(),
+            });
+        }
+        let key = Key::parse(
+            &normalized.iter().skip(offset).collect::<String>(),
+            &alphabet,
+        )?;
+
+        Ok(RunningKeyCipher {
+            key,
+            alphabet,
+            non_alpha: NonAlpha::default(),
+            tableau: Tableau::default(),
+        })
+    }
+
+    /// Sets how characters outside the alphabet are handled.
+    pub fn with_non_alpha(mut self, non_alpha: NonAlpha) -> RunningKeyCipher {
+        self.non_alpha = non_alpha;
+        self
+    }
+
+    /// Returns how characters outside the alphabet are handled.
+    pub fn non_alpha(&self) -> NonAlpha {
+        self.non_alpha
+    }
+
+    /// Sets the tableau letters are combined with the key by.
+    pub fn with_tableau(mut self, tableau: Tableau) -> RunningKeyCipher {
+        self.tableau = tableau;
+        self
+    }
+
+    /// Returns the tableau letters are combined with the key by.
+    pub fn tableau(&self) -> Tableau {
+        self.tableau
+    }
+
+    /// Returns the normalized key, from the offset on.
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    /// Returns the alphabet this cipher rotates characters within.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Encrypts `plain_text`, returning the cipher text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is too short for `plain_text`, or if `plain_text`
+    /// contains a character that is rejected under [`NonAlpha::Reject`].
+    pub fn encrypt(&self, plain_text: &str) -> String {
+        self.try_encrypt(plain_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Decrypts `cipher_text`, returning the plain text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is too short for `cipher_text`, or if `cipher_text`
+    /// contains a character that is rejected under [`NonAlpha::Reject`].
+    pub fn decrypt(&self, cipher_text: &str) -> String {
+        self.try_decrypt(cipher_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts `plain_text`, or reports why it can't be.
+    pub fn try_encrypt(&self, plain_text: &str) -> Result<String, CipherError> {
+        self.transform(plain_text, self.tableau.rotation(false))
+    }
+
+    /// Decrypts `cipher_text`, or reports why it can't be.
+    pub fn try_decrypt(&self, cipher_text: &str) -> Result<String, CipherError> {
+        self.transform(cipher_text, self.tableau.rotation(true))
+    }
+
+    fn transform(
+        &self,
+        val: &str,
+        rotate: fn(u32, u32, u32) -> u32,
+    ) -> Result<String, CipherError> {
+        // Check the key is long enough first, so the key never wraps around.
+        let needed = key_positions_needed(&self.alphabet, self.non_alpha, val);
+        let available = self.key.shifts().len();
+        if needed > available {
+            return Err(CipherError::KeyTooShort { needed, available });
+        }
+
+        let mut keystream = RepeatingKey {
+            shifts: self.key.shifts(),
+            pos: 0,
+            n: self.alphabet.size() as u32,
+            rotate,
+        };
+        transform(&self.alphabet, self.non_alpha, val, &mut keystream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VigenereCipher;
+
+    const BOOK: &str = "Call me Ishmael. Some years ago, never mind how long precisely, \
+        having little or no money in my purse, and nothing particular to interest me \
+        on shore, I thought I would sail about a little and see the watery part of \
+        the world.";
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = RunningKeyCipher::new(BOOK, 13).with_non_alpha(NonAlpha::Preserve);
+        assert!(cipher.key().starts_with("SOMEYEARSAGO"));
+
+        let plain_text = "Meet me at the harbour at midnight.";
+        let cipher_text = cipher.encrypt(plain_text);
+        assert_eq!(plain_text, cipher.decrypt(&cipher_text));
+        assert_eq!(
+            VigenereCipher::new("SOMEYEARSAGONEVERMINDHOWLONGPRECISELY")
+                .with_non_alpha(NonAlpha::Preserve)
+                .encrypt(plain_text),
+            cipher_text
+        );
+    }
+
+    #[test]
+    fn test_key_too_short() {
+        let cipher = RunningKeyCipher::new("ABC DEF", 2);
+        assert_eq!("CDEF", cipher.key());
+        assert_eq!(Ok("CEGI".to_string()), cipher.try_encrypt("ABCD"));
+        assert_eq!(
+            Err(CipherError::KeyTooShort {
+                needed: 5,
+                available: 4
+            }),
+            cipher.try_encrypt("ABCDE")
+        );
+
+        let cipher = cipher.with_non_alpha(NonAlpha::PreserveAndAdvance);
+        assert_eq!(
+            Err(CipherError::KeyTooShort {
+                needed: 5,
+                available: 4
+            }),
+            cipher.try_decrypt("AB CD")
+        );
+    }
+
+    #[test]
+    fn test_offset_past_end() {
+        // At least one letter is needed after the offset.
+        assert_eq!(
+            Err(CipherError::KeyTooShort {
+                needed: 4,
+                available: 3
+            }),
+            RunningKeyCipher::try_new("ABC", 3)
+        );
+        assert_eq!(
+            Err(CipherError::KeyTooShort {
+                needed: 6,
+                available: 3
+            }),
+            RunningKeyCipher::try_new("a, b, c!", 5)
+        );
+        assert!(RunningKeyCipher::try_new("ABC", 2).is_ok());
+    }
+
+    #[test]
+    fn test_empty_key() {
+        assert_eq!(Err(CipherError::EmptyKey), RunningKeyCipher::try_new("", 3));
+        assert_eq!(
+            Err(CipherError::EmptyKey),
+            RunningKeyCipher::try_new("123", 0)
+        );
+    }
+
+    #[test]
+    fn test_tableau() {
+        let cipher = RunningKeyCipher::new(BOOK, 0).with_tableau(Tableau::Beaufort);
+        assert_eq!(
+            "ATTACKATDAWN",
+            cipher.encrypt(&cipher.encrypt("ATTACKATDAWN"))
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Encrypting and decrypting text as it passes through [`Read`] and
+//! [`Write`].
+//!
+//! [`VigenereCipher::encrypt`] needs the whole text in memory and starts at
+//! the beginning of the key every time it is called.  The stream types here
+//! encipher text a chunk at a time, carrying the position in the key from one
+//! chunk to the next, so a file of any size can be handled in a fixed amount
+//! of memory.  The output is the same as enciphering the whole text at once.
+//!
+//! The text must be UTF-8, but chunks may split a character anywhere; the
+//! start of a split character is held back until the rest of it arrives.
+//! Errors are reported as [`io::ErrorKind::InvalidData`], wrapping a
+//! [`CipherError`] whose positions count from the start of the stream.  A
+//! stream should not be used again after it has returned an error.
+
+use std::io::{self, Read, Write};
+use std::mem;
+use std::str;
+
+use crate::cipher::VigenereCipher;
+use crate::error::CipherError;
+
+// How much a VigenereReader reads from the underlying reader at a time.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+// Everything a stream needs to pick up where the previous chunk left off.
+struct Progress {
+    cipher: VigenereCipher,
+    decrypt: bool,
+    key_pos: usize,
+    // Characters enciphered so far, so errors can give positions from the
+    // start of the stream rather than the start of the chunk.
+    chars_seen: usize,
+    // The first bytes of a character split across chunks.
+    partial: Vec<u8>,
+}
+
+impl Progress {
+    fn new(cipher: VigenereCipher, decrypt: bool) -> Progress {
+        Progress {
+            cipher,
+            decrypt,
+            key_pos: 0,
+            chars_seen: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    // Enciphers the held back bytes followed by `bytes`, up to the end of the
+    // last complete character, and appends the result to `out`.
+    fn process(&mut self, bytes: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        let joined;
+        let input = if self.partial.is_empty() {
+            bytes
+        } else {
+            let mut partial = mem::take(&mut self.partial);
+            partial.extend_from_slice(bytes);
+            joined = partial;
+            &joined[..]
+        };
+
+        let text = match str::from_utf8(input) {
+            Ok(text) => text,
+            // The input stops part way through a character.
+            Err(e) if e.error_len().is_none() => {
+                let (text, rest) = input.split_at(e.valid_up_to());
+                self.partial = rest.to_vec();
+                str::from_utf8(text).unwrap()
+            }
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+
+        let enciphered = self
+            .cipher
+            .transform(text, self.decrypt, &mut self.key_pos)
+            .map_err(|e| {
+                let e = match e {
+                    CipherError::InvalidInputChar { pos, ch } => CipherError::InvalidInputChar {
+                        pos: self.chars_seen + pos,
+                        ch,
+                    },
+                    e => e,
+                };
+                io::Error::new(io::ErrorKind::InvalidData, e)
+            })?;
+        self.chars_seen += text.chars().count();
+        out.extend_from_slice(enciphered.as_bytes());
+        Ok(())
+    }
+
+    // Checks that the stream didn't end part way through a character.
+    fn finish(&self) -> io::Result<()> {
+        if self.partial.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream ended part way through a UTF-8 character",
+            ))
+        }
+    }
+}
+
+/// A writer that enciphers everything written to it before passing it on to
+/// another writer.
+///
+/// Call [`finish`](Self::finish) when done to flush the underlying writer
+/// and to check that the text didn't end part way through a character.
+///
+/// ```
+/// use std::io::Write;
+/// use vigenere_cipher::{VigenereCipher, VigenereWriter};
+///
+/// let mut writer = VigenereWriter::encrypting(VigenereCipher::new("DUH"), Vec::new());
+/// writer.write_all(b"CRY").unwrap();
+/// writer.write_all(b"PTO").unwrap();
+/// assert_eq!(b"FLFSNV", &writer.finish().unwrap()[..]);
+/// ```
+pub struct VigenereWriter<W: Write> {
+    inner: W,
+    progress: Progress,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> VigenereWriter<W> {
+    /// Creates a writer that encrypts with `cipher` and writes the cipher
+    /// text to `inner`.
+    pub fn encrypting(cipher: VigenereCipher, inner: W) -> VigenereWriter<W> {
+        VigenereWriter::new(cipher, inner, false)
+    }
+
+    /// Creates a writer that decrypts with `cipher` and writes the plain text
+    /// to `inner`.
+    pub fn decrypting(cipher: VigenereCipher, inner: W) -> VigenereWriter<W> {
+        VigenereWriter::new(cipher, inner, true)
+    }
+
+    fn new(cipher: VigenereCipher, inner: W, decrypt: bool) -> VigenereWriter<W> {
+        VigenereWriter {
+            inner,
+            progress: Progress::new(cipher, decrypt),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.  Writing to it
+    /// directly leaves that text out of the cipher's key position.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Flushes the underlying writer and returns it, or reports that the
+    /// text ended part way through a character.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.progress.finish()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for VigenereWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.clear();
+        self.progress.process(buf, &mut self.buf)?;
+        self.inner.write_all(&self.buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader that enciphers everything read through it from another reader.
+///
+/// ```
+/// use std::io::Read;
+/// use vigenere_cipher::{VigenereCipher, VigenereReader};
+///
+/// let mut reader = VigenereReader::decrypting(VigenereCipher::new("DUH"), &b"FLFSNV"[..]);
+/// let mut plain_text = String::new();
+/// reader.read_to_string(&mut plain_text).unwrap();
+/// assert_eq!("CRYPTO", plain_text);
+/// ```
+pub struct VigenereReader<R: Read> {
+    inner: R,
+    progress: Progress,
+    chunk: Box<[u8]>,
+    // Enciphered text that hasn't been read yet, from `out_pos` on.
+    out: Vec<u8>,
+    out_pos: usize,
+}
+
+impl<R: Read> VigenereReader<R> {
+    /// Creates a reader that encrypts the plain text read from `inner` with
+    /// `cipher`.
+    pub fn encrypting(cipher: VigenereCipher, inner: R) -> VigenereReader<R> {
+        VigenereReader::new(cipher, inner, false)
+    }
+
+    /// Creates a reader that decrypts the cipher text read from `inner` with
+    /// `cipher`.
+    pub fn decrypting(cipher: VigenereCipher, inner: R) -> VigenereReader<R> {
+        VigenereReader::new(cipher, inner, true)
+    }
+
+    fn new(cipher: VigenereCipher, inner: R, decrypt: bool) -> VigenereReader<R> {
+        VigenereReader {
+            inner,
+            progress: Progress::new(cipher, decrypt),
+            chunk: vec![0; CHUNK_SIZE].into_boxed_slice(),
+            out: Vec::new(),
+            out_pos: 0,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader.  Anything already read from it but not
+    /// yet read through this reader is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for VigenereReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // A chunk can be all held back bytes, so keep reading until there is
+        // something to hand out or the underlying reader runs dry.
+        while self.out_pos == self.out.len() {
+            let n = self.inner.read(&mut self.chunk)?;
+            self.out.clear();
+            self.out_pos = 0;
+            if n == 0 {
+                self.progress.finish()?;
+                return Ok(0);
+            }
+            self.progress.process(&self.chunk[..n], &mut self.out)?;
+        }
+
+        let n = buf.len().min(self.out.len() - self.out_pos);
+        buf[..n].copy_from_slice(&self.out[self.out_pos..self.out_pos + n]);
+        self.out_pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alphabet, NonAlpha};
+
+    // Hands out one byte per read, to split every character across chunks.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((&b, rest)) if !buf.is_empty() => {
+                    buf[0] = b;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    fn greek() -> VigenereCipher {
+        VigenereCipher::new_in("ΚΛΕΙΔΙ", Alphabet::greek()).with_non_alpha(NonAlpha::Preserve)
+    }
+
+    const GREEK_TEXT: &str = "Ἄλφα, βήτα, γάμμα καὶ δέλτα — ΑΒΓΔ!";
+
+    #[test]
+    fn test_writer_matches_encrypt() {
+        let cipher = greek();
+        let mut writer = VigenereWriter::encrypting(cipher.clone(), Vec::new());
+        for byte in GREEK_TEXT.as_bytes() {
+            writer.write_all(&[*byte]).unwrap();
+        }
+        let out = writer.finish().unwrap();
+        assert_eq!(cipher.encrypt(GREEK_TEXT), String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_reader_matches_decrypt() {
+        let cipher = greek();
+        let cipher_text = cipher.encrypt(GREEK_TEXT);
+        let mut reader = VigenereReader::decrypting(cipher, Trickle(cipher_text.as_bytes()));
+        let mut plain_text = String::new();
+        reader.read_to_string(&mut plain_text).unwrap();
+        assert_eq!(GREEK_TEXT, plain_text);
+    }
+
+    #[test]
+    fn test_error_positions_count_from_start() {
+        let mut writer = VigenereWriter::encrypting(VigenereCipher::new("DUH"), Vec::new());
+        writer.write_all(b"CRYPTO").unwrap();
+        let e = writer.write_all(b"AB1").unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, e.kind());
+        assert_eq!(
+            Some(&CipherError::InvalidInputChar { pos: 8, ch: '1' }),
+            e.get_ref().and_then(|e| e.downcast_ref::<CipherError>())
+        );
+    }
+
+    #[test]
+    fn test_truncated_character() {
+        let cipher = greek();
+        let mut writer = VigenereWriter::encrypting(cipher.clone(), Vec::new());
+        writer.write_all(&"Λ".as_bytes()[..1]).unwrap();
+        assert!(writer.finish().is_err());
+
+        let mut reader = VigenereReader::encrypting(cipher, &"Λ".as_bytes()[..1]);
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+
+        let mut writer = VigenereWriter::encrypting(VigenereCipher::new("DUH"), Vec::new());
+        assert!(writer.write_all(b"\xffCRYPTO").is_err());
+    }
+}
@@ -0,0 +1,449 @@
+//! Ciphers over alphabets of grapheme clusters rather than single
+//! characters.
+//!
+//! An [`Alphabet`](crate::Alphabet) already holds any Unicode scalar values,
+//! but what a reader sees as one letter is often several of them: an accented
+//! letter written with a combining mark, or a Devanagari consonant with its
+//! vowel sign.  A [`GraphemeAlphabet`] treats each such cluster as a single
+//! letter.  The alphabet, keys and text are all brought to the same
+//! [`Normalization`] first, so that text typed with precomposed characters
+//! and text typed with combining marks encipher the same way.
+//!
+//! This module is only available with the `unicode` feature, which is on by
+//! default.
+
+use std::collections::HashMap;
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::cipher::{Keystream, NonAlpha, RepeatingKey, Tableau};
+use crate::error::CipherError;
+
+/// The Unicode normalization form text is brought to before it is split into
+/// grapheme clusters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Canonical composition: accented letters are single characters where
+    /// Unicode has one for them.
+    #[default]
+    Nfc,
+    /// Canonical decomposition: accents are always separate combining marks.
+    Nfd,
+}
+
+impl Normalization {
+    /// Returns `text` in this normalization form.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Normalization::Nfc => text.nfc().collect(),
+            Normalization::Nfd => text.nfd().collect(),
+        }
+    }
+}
+
+/// An ordered set of distinct grapheme clusters.
+///
+/// ```
+/// use vigenere_cipher::{GraphemeAlphabet, Normalization};
+///
+/// // Devanagari KA with each of its first vowel signs.
+/// let alphabet = GraphemeAlphabet::new("ककाकिकी", Normalization::Nfc);
+/// assert_eq!(4, alphabet.size());
+/// assert_eq!(Some(2), alphabet.index_of("कि"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphemeAlphabet {
+    graphemes: Vec<String>,
+    index: HashMap<String, u32>,
+    // The lowercase form of every grapheme cluster, or nothing if some
+    // cluster can't be lowercased and brought back.
+    lowercase: HashMap<String, u32>,
+    normalization: Normalization,
+}
+
+impl GraphemeAlphabet {
+    /// Creates an alphabet from the grapheme clusters of `letters`, in
+    /// order, after bringing them to `normalization`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `letters` is empty or contains a grapheme cluster more than
+    /// once.  Use [`try_new`](Self::try_new) to handle that instead.
+    pub fn new(letters: &str, normalization: Normalization) -> GraphemeAlphabet {
+        GraphemeAlphabet::try_new(letters, normalization).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates an alphabet from the grapheme clusters of `letters`, in
+    /// order, or reports why they can't form one.
+    pub fn try_new(
+        letters: &str,
+        normalization: Normalization,
+    ) -> Result<GraphemeAlphabet, CipherError> {
+        let letters = normalization.apply(letters);
+        let mut index = HashMap::new();
+        let mut graphemes = Vec::new();
+
+        for (pos, grapheme) in letters.graphemes(true).enumerate() {
+            if index.insert(grapheme.to_string(), pos as u32).is_some() {
+                return Err(CipherError::DuplicateAlphabetGrapheme {
+                    pos,
+                    grapheme: grapheme.to_string(),
+                });
+            }
+            graphemes.push(grapheme.to_string());
+        }
+
+        if graphemes.is_empty() {
+            return Err(CipherError::EmptyAlphabet);
+        }
+
+        let lowercase = lowercase_forms(&graphemes, &index, normalization);
+        Ok(GraphemeAlphabet {
+            graphemes,
+            index,
+            lowercase,
+            normalization,
+        })
+    }
+
+    /// Returns the number of grapheme clusters in the alphabet.
+    pub fn size(&self) -> usize {
+        self.graphemes.len()
+    }
+
+    /// Returns the grapheme clusters of the alphabet, in order.
+    pub fn graphemes(&self) -> &[String] {
+        &self.graphemes
+    }
+
+    /// Returns the normalization form the alphabet and text are brought to.
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Converts `grapheme` to its position in the alphabet, or `None` if it
+    /// isn't in the alphabet.  `grapheme` must already be in the alphabet's
+    /// normalization form.
+    pub fn index_of(&self, grapheme: &str) -> Option<u32> {
+        self.index.get(grapheme).copied()
+    }
+
+    /// Converts a position in the alphabet back to its grapheme cluster.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than [`size`](Self::size).
+    pub fn grapheme_at(&self, i: u32) -> &str {
+        &self.graphemes[i as usize]
+    }
+
+    // Like index_of(), but also accepts the lowercase form of a grapheme in
+    // the alphabet.  The returned flag is set when it is a lowercase form.
+    fn fold_index(&self, grapheme: &str) -> Option<(u32, bool)> {
+        if let Some(i) = self.index_of(grapheme) {
+            return Some((i, false));
+        }
+        self.lowercase.get(grapheme).map(|&i| (i, true))
+    }
+
+    // Converts a position back to its grapheme cluster, lowercased when
+    // `lower` is set.
+    fn grapheme_at_case(&self, i: u32, lower: bool) -> String {
+        let grapheme = self.grapheme_at(i);
+        if lower {
+            self.normalization.apply(&grapheme.to_lowercase())
+        } else {
+            grapheme.to_string()
+        }
+    }
+}
+
+// Maps the lowercase form of every grapheme cluster to its position, as
+// Alphabet does for characters: only when every cluster has a lowercase form,
+// itself a single cluster outside the alphabet, that uppercases back to it.
+fn lowercase_forms(
+    graphemes: &[String],
+    index: &HashMap<String, u32>,
+    normalization: Normalization,
+) -> HashMap<String, u32> {
+    graphemes
+        .iter()
+        .zip(0..)
+        .map(|(grapheme, i)| {
+            let lower = normalization.apply(&grapheme.to_lowercase());
+            let round_trips = lower != *grapheme
+                && lower.graphemes(true).count() == 1
+                && !index.contains_key(&lower)
+                && normalization.apply(&lower.to_uppercase()) == *grapheme;
+            round_trips.then_some((lower, i))
+        })
+        .collect::<Option<HashMap<String, u32>>>()
+        .unwrap_or_default()
+}
+
+/// A Vigenère cipher over a [`GraphemeAlphabet`], bound to a key.
+///
+/// Every grapheme cluster of the key must be in the alphabet.  Text is
+/// handled the same way as by [`VigenereCipher`](crate::VigenereCipher),
+/// including [`with_non_alpha`](Self::with_non_alpha) and
+/// [`with_tableau`](Self::with_tableau), but a letter is a grapheme cluster
+/// and error positions count grapheme clusters.  Output is in the alphabet's
+/// normalization form.
+///
+/// ```
+/// use vigenere_cipher::{GraphemeAlphabet, GraphemeCipher, NonAlpha, Normalization};
+///
+/// let alphabet = GraphemeAlphabet::new("AÀÂBCÇDEÉÈÊËFGHIÎÏJKLMNOÔPQRSTUÙÛÜVWXYŸZ", Normalization::Nfc);
+/// let cipher = GraphemeCipher::new("CLÉ", alphabet).with_non_alpha(NonAlpha::Preserve);
+///
+/// // The same text with a combining acute accent.
+/// let decomposed = "Cafe\u{301} crème";
+/// let cipher_text = cipher.encrypt(decomposed);
+/// assert_eq!(cipher_text, cipher.encrypt("Café crème"));
+/// assert_eq!("Café crème", cipher.decrypt(&cipher_text));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphemeCipher {
+    key: String,
+    shifts: Vec<u32>,
+    alphabet: GraphemeAlphabet,
+    non_alpha: NonAlpha,
+    tableau: Tableau,
+}
+
+impl GraphemeCipher {
+    /// Creates a cipher that rotates grapheme clusters within `alphabet` and
+    /// uses `key` for every message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty or contains a grapheme cluster outside
+    /// `alphabet`.  Use [`try_new`](Self::try_new) to handle that instead.
+    pub fn new(key: &str, alphabet: GraphemeAlphabet) -> GraphemeCipher {
+        GraphemeCipher::try_new(key, alphabet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a cipher that rotates grapheme clusters within `alphabet`, or
+    /// reports why `key` can't be used with it.
+    pub fn try_new(key: &str, alphabet: GraphemeAlphabet) -> Result<GraphemeCipher, CipherError> {
+        let key = alphabet.normalization.apply(key);
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+
+        let shifts = key
+            .graphemes(true)
+            .enumerate()
+            .map(|(pos, grapheme)| {
+                alphabet
+                    .index_of(grapheme)
+                    .ok_or_else(|| CipherError::InvalidKeyGrapheme {
+                        pos,
+                        grapheme: grapheme.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<u32>, CipherError>>()?;
+
+        Ok(GraphemeCipher {
+            key,
+            shifts,
+            alphabet,
+            non_alpha: NonAlpha::default(),
+            tableau: Tableau::default(),
+        })
+    }
+
+    /// Sets how grapheme clusters outside the alphabet are handled.
+    pub fn with_non_alpha(mut self, non_alpha: NonAlpha) -> GraphemeCipher {
+        self.non_alpha = non_alpha;
+        self
+    }
+
+    /// Returns how grapheme clusters outside the alphabet are handled.
+    pub fn non_alpha(&self) -> NonAlpha {
+        self.non_alpha
+    }
+
+    /// Sets the tableau letters are combined with the key by.
+    pub fn with_tableau(mut self, tableau: Tableau) -> GraphemeCipher {
+        self.tableau = tableau;
+        self
+    }
+
+    /// Returns the tableau letters are combined with the key by.
+    pub fn tableau(&self) -> Tableau {
+        self.tableau
+    }
+
+    /// Returns the key, in the alphabet's normalization form.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the alphabet this cipher rotates grapheme clusters within.
+    pub fn alphabet(&self) -> &GraphemeAlphabet {
+        &self.alphabet
+    }
+
+    /// Encrypts `plain_text`, returning the cipher text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plain_text` contains a grapheme cluster that is rejected
+    /// under [`NonAlpha::Reject`].
+    pub fn encrypt(&self, plain_text: &str) -> String {
+        self.try_encrypt(plain_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Decrypts `cipher_text`, returning the plain text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cipher_text` contains a grapheme cluster that is rejected
+    /// under [`NonAlpha::Reject`].
+    pub fn decrypt(&self, cipher_text: &str) -> String {
+        self.try_decrypt(cipher_text)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Encrypts `plain_text`, or reports the first grapheme cluster that
+    /// can't be handled.
+    pub fn try_encrypt(&self, plain_text: &str) -> Result<String, CipherError> {
+        self.transform(plain_text, false)
+    }
+
+    /// Decrypts `cipher_text`, or reports the first grapheme cluster that
+    /// can't be handled.
+    pub fn try_decrypt(&self, cipher_text: &str) -> Result<String, CipherError> {
+        self.transform(cipher_text, true)
+    }
+
+    // The grapheme counterpart of cipher::transform().
+    fn transform(&self, val: &str, decrypt: bool) -> Result<String, CipherError> {
+        let val = self.alphabet.normalization.apply(val);
+        let mut keystream = RepeatingKey {
+            shifts: &self.shifts,
+            pos: 0,
+            n: self.alphabet.size() as u32,
+            rotate: self.tableau.rotation(decrypt),
+        };
+        let mut return_val = String::with_capacity(val.len());
+
+        for (pos, grapheme) in val.graphemes(true).enumerate() {
+            let letter = match self.non_alpha {
+                NonAlpha::Reject => self.alphabet.index_of(grapheme).map(|i| (i, false)),
+                _ => self.alphabet.fold_index(grapheme),
+            };
+
+            if let Some((i, lower)) = letter {
+                let index = keystream.letter(i);
+                return_val.push_str(&self.alphabet.grapheme_at_case(index, lower));
+                continue;
+            }
+
+            match self.non_alpha {
+                NonAlpha::Reject => {
+                    return Err(CipherError::InvalidInputGrapheme {
+                        pos,
+                        grapheme: grapheme.to_string(),
+                    })
+                }
+                NonAlpha::Preserve => return_val.push_str(grapheme),
+                NonAlpha::PreserveAndAdvance => {
+                    return_val.push_str(grapheme);
+                    keystream.advance();
+                }
+            }
+        }
+
+        Ok(return_val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alphabet, VigenereCipher};
+
+    const DEVANAGARI: &str = "ककाकिकीखखाखिखी";
+
+    #[test]
+    fn test_graphemes_are_letters() {
+        let alphabet = GraphemeAlphabet::new(DEVANAGARI, Normalization::Nfc);
+        assert_eq!(8, alphabet.size());
+
+        // खि is five letters on from का.
+        let cipher = GraphemeCipher::new("खा", alphabet);
+        assert_eq!("खि", cipher.encrypt("का"));
+        assert_eq!("का", cipher.decrypt("खि"));
+    }
+
+    #[test]
+    fn test_normalization() {
+        let nfc = GraphemeAlphabet::new("AÉIOU", Normalization::Nfc);
+        let nfd = GraphemeAlphabet::new("AÉIOU", Normalization::Nfd);
+        assert_eq!(Some(1), nfc.index_of("\u{c9}"));
+        assert_eq!(None, nfc.index_of("E\u{301}"));
+        assert_eq!(Some(1), nfd.index_of("E\u{301}"));
+
+        let cipher = GraphemeCipher::new("E\u{301}", nfd);
+        assert_eq!("E\u{301}", cipher.encrypt("A"));
+        assert_eq!("I", cipher.encrypt("\u{c9}"));
+    }
+
+    #[test]
+    fn test_matches_char_cipher() {
+        let alphabet = GraphemeAlphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ", Normalization::Nfc);
+        let grapheme = GraphemeCipher::new("LEMON", alphabet).with_non_alpha(NonAlpha::Preserve);
+        let vigenere =
+            VigenereCipher::new_in("LEMON", Alphabet::latin()).with_non_alpha(NonAlpha::Preserve);
+        assert_eq!(
+            vigenere.encrypt("Attack at dawn!"),
+            grapheme.encrypt("Attack at dawn!")
+        );
+    }
+
+    #[test]
+    fn test_passthrough_round_trips_every_grapheme() {
+        let greek = GraphemeAlphabet::new("ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ", Normalization::Nfc);
+        let cipher = GraphemeCipher::new("ΚΛΕΙΔΙ", greek).with_non_alpha(NonAlpha::Preserve);
+        assert_eq!("λόγος", cipher.decrypt(&cipher.encrypt("λόγος")));
+
+        let alphanumeric = GraphemeAlphabet::new("ABCDEFGHIJ0123456789", Normalization::Nfc);
+        let cipher = GraphemeCipher::new("J9", alphanumeric).with_non_alpha(NonAlpha::Preserve);
+        assert_eq!("had", cipher.decrypt(&cipher.encrypt("had")));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Err(CipherError::DuplicateAlphabetGrapheme {
+                pos: 2,
+                grapheme: "\u{c9}".to_string()
+            }),
+            GraphemeAlphabet::try_new("\u{c9}AE\u{301}", Normalization::Nfc)
+        );
+        assert_eq!(
+            Err(CipherError::EmptyAlphabet),
+            GraphemeAlphabet::try_new("", Normalization::Nfd)
+        );
+
+        let alphabet = GraphemeAlphabet::new(DEVANAGARI, Normalization::Nfc);
+        assert_eq!(
+            Err(CipherError::InvalidKeyGrapheme {
+                pos: 1,
+                grapheme: "ग".to_string()
+            }),
+            GraphemeCipher::try_new("कग", alphabet.clone())
+        );
+        assert_eq!(
+            Err(CipherError::InvalidInputGrapheme {
+                pos: 1,
+                grapheme: "कु".to_string()
+            }),
+            GraphemeCipher::new("क", alphabet).try_encrypt("ककु")
+        );
+    }
+}
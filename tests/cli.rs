@@ -0,0 +1,126 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn vigenere(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vigenere"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The binary may exit before reading stdin, e.g. on a usage error.
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("vigenere-cli-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn test_stdin_to_stdout() {
+    let out = vigenere(&["encrypt", "--key", "DUH"], "CRYPTO\n");
+    assert_eq!(Some(0), out.status.code());
+    assert_eq!("FLFSNV\n", String::from_utf8(out.stdout).unwrap());
+
+    let out = vigenere(&["decrypt", "-k", "DUH"], "FLFSNV");
+    assert_eq!(Some(0), out.status.code());
+    assert_eq!("CRYPTO", String::from_utf8(out.stdout).unwrap());
+}
+
+#[test]
+fn test_files() {
+    let key_file = temp_path("key");
+    let input = temp_path("input");
+    let output = temp_path("output");
+    fs::write(&key_file, "LEMON\n").unwrap();
+    fs::write(&input, "Attack at dawn!\n").unwrap();
+
+    let out = vigenere(
+        &[
+            "encrypt",
+            "--key-file",
+            key_file.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            "--non-alpha",
+            "preserve",
+        ],
+        "",
+    );
+    assert_eq!(Some(0), out.status.code());
+    assert_eq!("Lxfopv ef rnhr!\n", fs::read_to_string(&output).unwrap());
+
+    for path in [key_file, input, output] {
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_exit_codes() {
+    let out = vigenere(&["encrypt", "-k", "DUH", "-i", "/nonexistent/input"], "");
+    assert_eq!(Some(1), out.status.code());
+
+    let out = vigenere(&["encrypt"], "CRYPTO");
+    assert_eq!(Some(2), out.status.code());
+
+    let out = vigenere(&["scramble", "-k", "DUH"], "CRYPTO");
+    assert_eq!(Some(2), out.status.code());
+
+    let out = vigenere(&["encrypt", "-k", "duh"], "CRYPTO");
+    assert_eq!(Some(3), out.status.code());
+
+    let out = vigenere(&["encrypt", "-k", "DUH"], "CRYPTO 101");
+    assert_eq!(Some(4), out.status.code());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("position 6"));
+
+    let path = temp_path("latin1");
+    fs::write(&path, b"CAF\xc9").unwrap();
+    let out = vigenere(&["encrypt", "-k", "DUH", "-i", path.to_str().unwrap()], "");
+    fs::remove_file(&path).unwrap();
+    assert_eq!(Some(4), out.status.code());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("not valid UTF-8"));
+}
+
+#[test]
+fn test_passphrase() {
+    let out = vigenere(&["encrypt", "--passphrase", "Lemon 7!"], "ATTACKATDAWN");
+    assert_eq!(Some(0), out.status.code());
+    assert_eq!("LXFOPVEFRNHR", String::from_utf8(out.stdout).unwrap());
+    assert_eq!(
+        "vigenere: removed from passphrase: ' ' '7' '!'\n",
+        String::from_utf8(out.stderr).unwrap()
+    );
+
+    let out = vigenere(
+        &["encrypt", "-p", "Lemon 7!", "--digits", "letters"],
+        "ATTACKATDAWN",
+    );
+    assert_eq!(Some(0), out.status.code());
+    let expected = vigenere(&["encrypt", "-k", "LEMONH"], "ATTACKATDAWN");
+    assert_eq!(expected.stdout, out.stdout);
+
+    let out = vigenere(&["encrypt", "-p", "2024"], "ATTACKATDAWN");
+    assert_eq!(Some(3), out.status.code());
+}
+
+#[cfg(feature = "unicode")]
+#[test]
+fn test_passphrase_transliterated() {
+    let out = vigenere(&["encrypt", "-p", "Lémon"], "ATTACKATDAWN");
+    assert_eq!(Some(0), out.status.code());
+    assert_eq!("LXFOPVEFRNHR", String::from_utf8(out.stdout).unwrap());
+    assert_eq!(
+        "vigenere: transliterated in passphrase: 'é' as E\n",
+        String::from_utf8(out.stderr).unwrap()
+    );
+}
@@ -0,0 +1,288 @@
+// Properties every cipher should have, checked over random keys, texts and
+// alphabets: decryption undoes encryption, the text keeps its length, and
+// enciphering is periodic in the key.
+
+use std::io::Write;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+use vigenere_cipher::{
+    Alphabet, AutokeyCipher, ByteCipher, ByteOp, NonAlpha, PortaCipher, Quagmire, QuagmireCipher,
+    RunningKeyCipher, Tableau, VigenereCipher, VigenereWriter,
+};
+#[cfg(feature = "unicode")]
+use vigenere_cipher::{GraphemeAlphabet, GraphemeCipher, Normalization};
+
+fn alphabet() -> impl Strategy<Value = Alphabet> {
+    prop_oneof![
+        Just(Alphabet::latin()),
+        Just(Alphabet::alphanumeric()),
+        Just(Alphabet::printable_ascii()),
+        Just(Alphabet::cyrillic()),
+        Just(Alphabet::greek()),
+    ]
+}
+
+fn even_alphabet() -> impl Strategy<Value = Alphabet> {
+    prop_oneof![
+        Just(Alphabet::latin()),
+        Just(Alphabet::alphanumeric()),
+        Just(Alphabet::greek()),
+    ]
+}
+
+fn tableau() -> impl Strategy<Value = Tableau> {
+    prop_oneof![
+        Just(Tableau::Vigenere),
+        Just(Tableau::Beaufort),
+        Just(Tableau::VariantBeaufort),
+    ]
+}
+
+fn non_alpha() -> impl Strategy<Value = NonAlpha> {
+    prop_oneof![
+        Just(NonAlpha::Reject),
+        Just(NonAlpha::Preserve),
+        Just(NonAlpha::PreserveAndAdvance),
+    ]
+}
+
+// Between `min` and `max` characters of `alphabet`.
+fn letters(alphabet: &Alphabet, min: usize, max: usize) -> impl Strategy<Value = String> {
+    vec(select(alphabet.chars().to_vec()), min..max).prop_map(|chars| chars.into_iter().collect())
+}
+
+// Text for `non_alpha`: under the passthrough modes, letters of `alphabet`
+// mixed with their lowercase forms, with characters that only uppercase into
+// the alphabet, and with characters from outside it.
+fn text(alphabet: &Alphabet, non_alpha: NonAlpha) -> impl Strategy<Value = String> {
+    let mut chars = alphabet.chars().to_vec();
+    if non_alpha != NonAlpha::Reject {
+        chars.extend(alphabet.chars().iter().flat_map(|c| c.to_lowercase()));
+        chars.extend(['\u{3c2}', '\u{131}', '\u{17f}']);
+        chars.extend([' ', ',', '!', '\n', '\u{e9}', '\u{1f511}']);
+    }
+    vec(select(chars), 0..80).prop_map(|chars| chars.into_iter().collect())
+}
+
+// An alphabet with a key and a text for it.
+fn vigenere_case() -> impl Strategy<Value = (Alphabet, String, NonAlpha, Tableau, String)> {
+    (alphabet(), non_alpha(), tableau()).prop_flat_map(|(alphabet, non_alpha, tableau)| {
+        (
+            letters(&alphabet, 1, 12),
+            Just(non_alpha),
+            Just(tableau),
+            text(&alphabet, non_alpha),
+            Just(alphabet),
+        )
+            .prop_map(|(key, non_alpha, tableau, text, alphabet)| {
+                (alphabet, key, non_alpha, tableau, text)
+            })
+    })
+}
+
+// An alphabet the Porta cipher can split in half, with a key and a text for
+// it.
+fn porta_case() -> impl Strategy<Value = (Alphabet, String, NonAlpha, String)> {
+    (even_alphabet(), non_alpha()).prop_flat_map(|(alphabet, non_alpha)| {
+        (
+            letters(&alphabet, 1, 12),
+            Just(non_alpha),
+            text(&alphabet, non_alpha),
+            Just(alphabet),
+        )
+            .prop_map(|(key, non_alpha, text, alphabet)| (alphabet, key, non_alpha, text))
+    })
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+proptest! {
+    #[test]
+    fn vigenere_round_trip((alphabet, key, non_alpha, tableau, text) in vigenere_case()) {
+        let cipher = VigenereCipher::new_in(&key, alphabet)
+            .with_non_alpha(non_alpha)
+            .with_tableau(tableau);
+        let cipher_text = cipher.encrypt(&text);
+        prop_assert_eq!(char_len(&text), char_len(&cipher_text));
+        prop_assert_eq!(&text, &cipher.decrypt(&cipher_text));
+    }
+
+    #[test]
+    fn vigenere_preserves_non_letters((alphabet, key, _, tableau, text) in vigenere_case()) {
+        let cipher = VigenereCipher::new_in(&key, alphabet.clone())
+            .with_non_alpha(NonAlpha::Preserve)
+            .with_tableau(tableau);
+        for (plain, enciphered) in text.chars().zip(cipher.encrypt(&text).chars()) {
+            let is_letter = alphabet.contains(plain)
+                || plain.to_uppercase().any(|upper| alphabet.contains(upper));
+            if !is_letter {
+                prop_assert_eq!(plain, enciphered);
+            }
+        }
+    }
+
+    #[test]
+    fn vigenere_repeated_key_is_the_same_key(
+        (alphabet, key, non_alpha, tableau, text) in vigenere_case(),
+        copies in 2..5usize,
+    ) {
+        let cipher = |key: &str| {
+            VigenereCipher::new_in(key, alphabet.clone())
+                .with_non_alpha(non_alpha)
+                .with_tableau(tableau)
+        };
+        prop_assert_eq!(
+            cipher(&key).encrypt(&text),
+            cipher(&key.repeat(copies)).encrypt(&text)
+        );
+    }
+
+    #[test]
+    fn vigenere_key_position_is_periodic(
+        (alphabet, key, _, tableau, text) in vigenere_case(),
+        periods in 0..4usize,
+    ) {
+        // Enciphering a whole number of periods of text returns the key to
+        // where it started, so the rest enciphers the same on its own.
+        let split = (key.chars().count() * periods).min(char_len(&text));
+        let split = split - split % key.chars().count();
+        let (head, tail) = text.split_at(text.char_indices().nth(split).map_or(text.len(), |(i, _)| i));
+
+        let cipher = VigenereCipher::new_in(&key, alphabet)
+            .with_non_alpha(NonAlpha::PreserveAndAdvance)
+            .with_tableau(tableau);
+        prop_assert_eq!(
+            cipher.encrypt(&text),
+            cipher.encrypt(head) + &cipher.encrypt(tail)
+        );
+    }
+
+    #[test]
+    fn vigenere_in_place_matches_encrypt(
+        (alphabet, key, non_alpha, tableau, text) in vigenere_case()
+    ) {
+        let cipher = VigenereCipher::new_in(&key, alphabet)
+            .with_non_alpha(non_alpha)
+            .with_tableau(tableau);
+        let mut data = text.clone().into_bytes();
+        match cipher.try_encrypt_in_place(&mut data) {
+            Ok(()) => prop_assert_eq!(cipher.encrypt(&text).into_bytes(), data),
+            Err(_) => prop_assert!(cipher.alphabet().chars().iter().any(|c| !c.is_ascii())),
+        }
+    }
+
+    #[test]
+    fn vigenere_stream_matches_encrypt(
+        (alphabet, key, non_alpha, tableau, text) in vigenere_case(),
+        splits in vec(any::<prop::sample::Index>(), 0..4),
+    ) {
+        let cipher = VigenereCipher::new_in(&key, alphabet)
+            .with_non_alpha(non_alpha)
+            .with_tableau(tableau);
+
+        // Split the text at arbitrary bytes, even inside a character.
+        let bytes = text.as_bytes();
+        let mut cuts = splits.iter().map(|i| i.index(bytes.len() + 1)).collect::<Vec<usize>>();
+        cuts.sort();
+        let mut writer = VigenereWriter::encrypting(cipher.clone(), Vec::new());
+        let mut start = 0;
+        for cut in cuts.into_iter().chain([bytes.len()]) {
+            writer.write_all(&bytes[start..cut]).unwrap();
+            start = cut;
+        }
+        prop_assert_eq!(cipher.encrypt(&text).into_bytes(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn autokey_round_trip((alphabet, primer, non_alpha, _, text) in vigenere_case()) {
+        let cipher = AutokeyCipher::new_in(&primer, alphabet).with_non_alpha(non_alpha);
+        let cipher_text = cipher.encrypt(&text);
+        prop_assert_eq!(char_len(&text), char_len(&cipher_text));
+        prop_assert_eq!(&text, &cipher.decrypt(&cipher_text));
+    }
+
+    #[test]
+    fn running_key_round_trip(
+        (alphabet, _, non_alpha, tableau, text) in vigenere_case(),
+        offset in 0..10usize,
+    ) {
+        let key_text = alphabet.chars().iter().cycle().take(char_len(&text) + 20).collect::<String>();
+        let cipher = RunningKeyCipher::new_in(&key_text, offset, alphabet)
+            .with_non_alpha(non_alpha)
+            .with_tableau(tableau);
+        let cipher_text = cipher.encrypt(&text);
+        prop_assert_eq!(char_len(&text), char_len(&cipher_text));
+        prop_assert_eq!(&text, &cipher.decrypt(&cipher_text));
+    }
+
+    #[test]
+    fn quagmire_round_trip(
+        (alphabet, key, non_alpha, _, text) in vigenere_case(),
+        variant in 0..4usize,
+        indicator in any::<prop::sample::Index>(),
+    ) {
+        let keyword = alphabet.chars().iter().rev().step_by(3).collect::<String>();
+        let other = alphabet.chars().iter().step_by(2).collect::<String>();
+        let variant = match variant {
+            0 => Quagmire::I { keyword: &keyword },
+            1 => Quagmire::II { keyword: &keyword },
+            2 => Quagmire::III { keyword: &keyword },
+            _ => Quagmire::IV { plain_keyword: &keyword, cipher_keyword: &other },
+        };
+        let indicator = *indicator.get(alphabet.chars());
+
+        let cipher = QuagmireCipher::new_in(variant, &key, alphabet)
+            .with_indicator(indicator)
+            .with_non_alpha(non_alpha);
+        let cipher_text = cipher.encrypt(&text);
+        prop_assert_eq!(char_len(&text), char_len(&cipher_text));
+        prop_assert_eq!(&text, &cipher.decrypt(&cipher_text));
+    }
+
+    #[test]
+    fn porta_is_reciprocal((alphabet, key, non_alpha, text) in porta_case()) {
+        let cipher = PortaCipher::new_in(&key, alphabet).with_non_alpha(non_alpha);
+        let cipher_text = cipher.apply(&text);
+        prop_assert_eq!(char_len(&text), char_len(&cipher_text));
+        prop_assert_eq!(&text, &cipher.apply(&cipher_text));
+    }
+
+    #[test]
+    fn bytes_round_trip(
+        key in vec(any::<u8>(), 1..16),
+        data in vec(any::<u8>(), 0..200),
+        xor in any::<bool>(),
+    ) {
+        let op = if xor { ByteOp::Xor } else { ByteOp::Add };
+        let cipher = ByteCipher::new(&key).with_op(op);
+        let cipher_text = cipher.encrypt(&data);
+        prop_assert_eq!(data.len(), cipher_text.len());
+        prop_assert_eq!(&data, &cipher.decrypt(&cipher_text));
+    }
+}
+
+#[cfg(feature = "unicode")]
+proptest! {
+    #[test]
+    fn grapheme_round_trip(
+        key in vec(0..8usize, 1..8),
+        text in vec(0..10usize, 0..40),
+        tableau in tableau(),
+    ) {
+        // Letters of Devanagari KA and KHA with vowel signs, and two
+        // characters outside the alphabet.
+        let graphemes = ["क", "का", "कि", "की", "ख", "खा", "खि", "खी", " ", "।"];
+        let alphabet = GraphemeAlphabet::new(&graphemes[..8].concat(), Normalization::Nfc);
+        let key = key.iter().map(|&i| graphemes[i]).collect::<String>();
+        let text = text.iter().map(|&i| graphemes[i]).collect::<String>();
+
+        let cipher = GraphemeCipher::new(&key, alphabet)
+            .with_non_alpha(NonAlpha::Preserve)
+            .with_tableau(tableau);
+        prop_assert_eq!(&text, &cipher.decrypt(&cipher.encrypt(&text)));
+    }
+}